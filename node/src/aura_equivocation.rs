@@ -0,0 +1,76 @@
+//! Detection of Aura authors that seal two blocks in the same slot.
+//!
+//! Every imported header is remembered by its slot for a while. When a different header for a
+//! slot we already saw comes in, both are reported to the runtime through the
+//! `AuraEquivocationApi`, which checks them and submits the report to the transaction pool.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use futures::StreamExt;
+use pallet_aura_equivocation_runtime_api::AuraEquivocationApi;
+use sc_client_api::BlockchainEvents;
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT}};
+
+/// How many slots back headers are remembered.
+///
+/// Reports are checked against the current authorities, so there is no point in keeping headers
+/// for much longer than a session.
+const MAX_SLOT_AGE: u64 = 1_000;
+
+/// Watch block imports for equivocations and report them, until the import stream ends.
+pub async fn report_equivocations<B, C>(client: Arc<C>)
+where
+	B: BlockT,
+	C: BlockchainEvents<B> + HeaderBackend<B> + ProvideRuntimeApi<B>,
+	C::Api: AuraEquivocationApi<B>,
+{
+	let mut seen = BTreeMap::<u64, B::Header>::new();
+	let mut reported = BTreeSet::<u64>::new();
+	let mut imports = client.import_notification_stream();
+
+	while let Some(notification) = imports.next().await {
+		let header = notification.header;
+		let slot = match pallet_aura_equivocation::slot_of(&header) {
+			Some(slot) => slot,
+			None => continue,
+		};
+
+		if let Some(latest) = seen.keys().next_back().copied() {
+			if slot.saturating_add(MAX_SLOT_AGE) < latest {
+				continue;
+			}
+			let oldest = latest.saturating_sub(MAX_SLOT_AGE);
+			seen = seen.split_off(&oldest);
+			reported = reported.split_off(&oldest);
+		}
+
+		let first = match seen.get(&slot) {
+			Some(first) if first.hash() != header.hash() => first.clone(),
+			Some(_) => continue,
+			None => {
+				seen.insert(slot, header);
+				continue;
+			},
+		};
+		if !reported.insert(slot) {
+			continue;
+		}
+
+		log::info!(
+			target: "aura",
+			"Equivocation in slot {}: {:?} and {:?}",
+			slot,
+			first.hash(),
+			header.hash(),
+		);
+		let best = BlockId::Hash(client.info().best_hash);
+		match client.runtime_api().submit_report_equivocation_unsigned_extrinsic(&best, first, header) {
+			Ok(Some(())) => {},
+			Ok(None) => log::warn!(target: "aura", "Failed to submit report for slot {}", slot),
+			Err(e) => log::warn!(target: "aura", "Failed to report equivocation in slot {}: {:?}", slot, e),
+		}
+	}
+}
@@ -1,7 +1,9 @@
 use sp_core::{Pair, Public, sr25519};
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig,
-	SudoConfig, SystemConfig, WASM_BINARY, Signature
+	AccountId, AuraConfig, BalancesConfig, ContractsConfig, CouncilConfig, CouncilMembershipConfig,
+	DemocracyConfig, GenesisConfig, GrandpaConfig, IdentityRegistrarsConfig, IndicesConfig,
+	NamesConfig, SessionConfig, SudoConfig, SystemConfig, TemplateModuleConfig, TreasuryConfig,
+	ValidatorSetConfig, WASM_BINARY, Signature, opaque::SessionKeys,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_finality_grandpa::AuthorityId as GrandpaId;
@@ -30,14 +32,24 @@ pub fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
+/// Generate a validator account and its session keys.
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, AuraId, GrandpaId) {
 	(
+		get_account_id_from_seed::<sr25519::Public>(s),
 		get_from_seed::<AuraId>(s),
 		get_from_seed::<GrandpaId>(s),
 	)
 }
 
+/// Name the account of `seed` after it, e.g. `alice` for `Alice`.
+fn name_from_seed(seed: &str) -> (AccountId, Vec<u8>) {
+	(get_account_id_from_seed::<sr25519::Public>(seed), seed.to_lowercase().into_bytes())
+}
+
+fn session_keys(aura: AuraId, grandpa: GrandpaId) -> SessionKeys {
+	SessionKeys { aura, grandpa }
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
@@ -62,6 +74,22 @@ pub fn development_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 			],
+			// Initial template values
+			vec![
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), 42),
+			],
+			// Initial council members
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+			],
+			// Names of the validators and the sudo account
+			vec![
+				name_from_seed("Alice"),
+			],
+			// Initial identity registrars
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+			],
 			true,
 		),
 		// Bootnodes
@@ -110,6 +138,26 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 				get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 				get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 			],
+			// Initial template values
+			vec![
+				(get_account_id_from_seed::<sr25519::Public>("Alice"), 42),
+				(get_account_id_from_seed::<sr25519::Public>("Bob"), 7),
+			],
+			// Initial council members
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+				get_account_id_from_seed::<sr25519::Public>("Bob"),
+				get_account_id_from_seed::<sr25519::Public>("Charlie"),
+			],
+			// Names of the validators and the sudo account
+			vec![
+				name_from_seed("Alice"),
+				name_from_seed("Bob"),
+			],
+			// Initial identity registrars
+			vec![
+				get_account_id_from_seed::<sr25519::Public>("Alice"),
+			],
 			true,
 		),
 		// Bootnodes
@@ -128,10 +176,14 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
 	wasm_binary: &[u8],
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	initial_authorities: Vec<(AccountId, AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
-	_enable_println: bool,
+	template_values: Vec<(AccountId, u32)>,
+	council_members: Vec<AccountId>,
+	names: Vec<(AccountId, Vec<u8>)>,
+	registrars: Vec<AccountId>,
+	enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
 		frame_system: Some(SystemConfig {
@@ -143,15 +195,48 @@ fn testnet_genesis(
 			// Configure endowed accounts with initial balance of 1 << 60.
 			balances: endowed_accounts.iter().cloned().map(|k|(k, 1 << 60)).collect(),
 		}),
+		pallet_indices: Some(IndicesConfig {
+			// Pre-assign indices to endowed accounts, in order.
+			indices: endowed_accounts.iter().cloned().enumerate().map(|(i, k)| (i as u32, k)).collect(),
+		}),
+		pallet_validator_set: Some(ValidatorSetConfig {
+			validators: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+		}),
+		pallet_session: Some(SessionConfig {
+			keys: initial_authorities.iter().map(|x| {
+				(x.0.clone(), x.0.clone(), session_keys(x.1.clone(), x.2.clone()))
+			}).collect(),
+		}),
+		// The authorities are set by `pallet_session` from the session keys above.
 		pallet_aura: Some(AuraConfig {
-			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
+			authorities: vec![],
 		}),
 		pallet_grandpa: Some(GrandpaConfig {
-			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect(),
+			authorities: vec![],
 		}),
 		pallet_sudo: Some(SudoConfig {
 			// Assign network admin rights.
 			key: root_key,
 		}),
+		template: Some(TemplateModuleConfig {
+			something: template_values,
+		}),
+		// The council is initialized by `CouncilMembership`.
+		pallet_collective_Instance1: Some(CouncilConfig::default()),
+		pallet_membership_Instance1: Some(CouncilMembershipConfig {
+			members: council_members,
+			phantom: Default::default(),
+		}),
+		pallet_democracy: Some(DemocracyConfig::default()),
+		pallet_treasury: Some(TreasuryConfig::default()),
+		pallet_contracts: Some(ContractsConfig {
+			// `println` in contracts is only useful, and only safe, on development chains.
+			current_schedule: pallet_contracts::Schedule {
+				enable_println,
+				..Default::default()
+			},
+		}),
+		pallet_identity_registrars: Some(IdentityRegistrarsConfig { registrars }),
+		pallet_names: Some(NamesConfig { names }),
 	}
 }
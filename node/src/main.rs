@@ -1,6 +1,7 @@
 //! Substrate Node Template CLI library.
 #![warn(missing_docs)]
 
+mod aura_equivocation;
 mod chain_spec;
 #[macro_use]
 mod service;
@@ -179,6 +179,12 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		},
 	)?;
 
+	// Reports are submitted to the local transaction pool, so any full node can make them.
+	task_manager.spawn_handle().spawn(
+		"aura-equivocation",
+		crate::aura_equivocation::report_equivocations(client.clone()),
+	);
+
 	if role.is_authority() {
 		let proposer = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
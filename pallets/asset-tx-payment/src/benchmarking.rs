@@ -0,0 +1,27 @@
+//! Benchmarking setup for pallet-asset-tx-payment
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::EnsureOrigin;
+use sp_runtime::{FixedPointNumber, FixedU128};
+use sp_std::prelude::*;
+#[allow(unused)]
+use crate::Pallet as AssetTxPayment;
+
+benchmarks! {
+	set_conversion_rate {
+		let origin = T::RateOrigin::successful_origin();
+		let asset = T::AssetId::default();
+		let rate = FixedU128::saturating_from_rational(3, 2);
+	}: _(origin, asset, Some(rate))
+	verify {
+		assert_eq!(AssetTxPayment::<T>::conversion_rate(asset), Some(rate));
+	}
+}
+
+impl_benchmark_test_suite!(
+	AssetTxPayment,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
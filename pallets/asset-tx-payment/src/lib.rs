@@ -0,0 +1,345 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Asset Transaction Payment Pallet
+//!
+//! Lets accounts pay transaction fees in an asset instead of the native currency, through the
+//! `ChargeAssetTxPayment` signed extension, which replaces
+//! `pallet_transaction_payment::ChargeTransactionPayment` in a runtime's `SignedExtra`.
+//!
+//! A transaction without an asset pays like with `ChargeTransactionPayment`. A transaction with
+//! an asset pays the native fee, tip included, converted at the asset's `ConversionRates` entry:
+//! `fee * rate` asset units. The fee is moved to `FeeCollector` before dispatch, and the part not
+//! needed after dispatch is refunded. Only assets `RateOrigin` set a rate for can pay fees.
+//!
+//! Only accounts that exist in `frame_system`, i.e. have a provider such as a native balance of
+//! at least the existential deposit, can pay in an asset. Otherwise an account holding nothing
+//! but assets could send transactions and have its nonce stored without ever paying for the
+//! storage.
+//!
+//! Assets are moved through `AssetTransfer`, so the pallet works with any asset implementation.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+use codec::{Encode, Decode};
+use frame_support::{
+	dispatch::{DispatchResult, Dispatchable},
+	traits::Get,
+	weights::{DispatchInfo, PostDispatchInfo},
+};
+use pallet_transaction_payment::{BalanceOf, ChargeTransactionPayment, FeeDetails, InclusionFee};
+use sp_runtime::{
+	traits::{DispatchInfoOf, PostDispatchInfoOf, SaturatedConversion, Saturating, SignedExtension, Zero},
+	transaction_validity::{
+		InvalidTransaction, TransactionValidity, TransactionValidityError, ValidTransaction,
+	},
+	FixedPointNumber, FixedPointOperand, FixedU128,
+};
+
+/// Moves assets between accounts.
+pub trait AssetTransfer<AccountId, AssetId, Balance> {
+	/// Move `amount` of `asset` from `from` to `to`.
+	fn transfer(asset: AssetId, from: &AccountId, to: &AccountId, amount: Balance) -> DispatchResult;
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::{
+		traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize},
+		FixedU128,
+	};
+	use super::{AssetTransfer, WeightInfo};
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_transaction_payment::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The identifier of an asset.
+		type AssetId: Member + Parameter + Copy + Default + MaybeSerializeDeserialize;
+		/// The balance type of assets.
+		type AssetBalance: Member + Parameter + AtLeast32BitUnsigned + Copy + Default;
+		/// Moves fees paid in assets.
+		type Assets: AssetTransfer<Self::AccountId, Self::AssetId, Self::AssetBalance>;
+		/// The account receiving the fees paid in assets.
+		type FeeCollector: Get<Self::AccountId>;
+		/// The origin allowed to set conversion rates.
+		type RateOrigin: EnsureOrigin<Self::Origin>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The asset units a native unit of fees converts to, for each asset that can pay fees.
+	#[pallet::storage]
+	#[pallet::getter(fn conversion_rate)]
+	pub type ConversionRates<T: Config> = StorageMap<_, Twox64Concat, T::AssetId, FixedU128>;
+
+	#[pallet::event]
+	#[pallet::metadata(
+		T::AccountId = "AccountId",
+		T::AssetId = "AssetId",
+		T::AssetBalance = "AssetBalance",
+	)]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The conversion rate of an asset was set, or removed. [asset, rate]
+		ConversionRateSet(T::AssetId, Option<FixedU128>),
+		/// A transaction fee was paid in an asset. [who, asset, fee]
+		AssetTxFeePaid(T::AccountId, T::AssetId, T::AssetBalance),
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the conversion rate of `asset`, or remove it so the asset cannot pay fees anymore.
+		///
+		/// The dispatch origin must be `RateOrigin`.
+		#[pallet::weight(T::WeightInfo::set_conversion_rate())]
+		pub fn set_conversion_rate(
+			origin: OriginFor<T>,
+			asset: T::AssetId,
+			rate: Option<FixedU128>,
+		) -> DispatchResultWithPostInfo {
+			T::RateOrigin::ensure_origin(origin)?;
+
+			<ConversionRates<T>>::mutate_exists(asset, |stored| *stored = rate);
+			Self::deposit_event(Event::ConversionRateSet(asset, rate));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		pub(crate) fn deposit_fee_paid(who: T::AccountId, asset: T::AssetId, fee: T::AssetBalance) {
+			Self::deposit_event(Event::AssetTxFeePaid(who, asset, fee));
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Convert a native `fee` to `asset` at `rate`, rounding down.
+	fn convert(rate: FixedU128, fee: BalanceOf<T>) -> T::AssetBalance {
+		rate.saturating_mul_int(fee.saturated_into::<u128>()).saturated_into()
+	}
+
+	/// Convert a native `fee` to `asset`, if the asset has a conversion rate.
+	pub fn to_asset_balance(asset: T::AssetId, fee: BalanceOf<T>) -> Option<T::AssetBalance> {
+		Self::conversion_rate(asset).map(|rate| Self::convert(rate, fee))
+	}
+
+	/// Convert native `details` to `asset`, if the asset has a conversion rate.
+	///
+	/// Each part is converted on its own, so they can add up to slightly less than the converted
+	/// total fee.
+	pub fn to_asset_fee_details(
+		asset: T::AssetId,
+		details: FeeDetails<BalanceOf<T>>,
+	) -> Option<FeeDetails<T::AssetBalance>> {
+		let rate = Self::conversion_rate(asset)?;
+		let convert = |fee| Self::convert(rate, fee);
+		Some(FeeDetails {
+			inclusion_fee: details.inclusion_fee.map(|fee| InclusionFee {
+				base_fee: convert(fee.base_fee),
+				len_fee: convert(fee.len_fee),
+				adjusted_weight_fee: convert(fee.adjusted_weight_fee),
+			}),
+			tip: convert(details.tip),
+		})
+	}
+}
+
+/// What a transaction paid before dispatch.
+pub enum InitialPayment<AssetId, AssetBalance, Balance, NativePre> {
+	/// Paid in the native currency, by `ChargeTransactionPayment`.
+	Native(NativePre),
+	/// Paid `fee` in `asset`, converted at `rate` from a native fee including `tip`.
+	Asset { asset: AssetId, rate: FixedU128, fee: AssetBalance, tip: Balance },
+}
+
+impl<AssetId, AssetBalance, Balance, NativePre: Default> Default
+	for InitialPayment<AssetId, AssetBalance, Balance, NativePre>
+{
+	fn default() -> Self {
+		InitialPayment::Native(Default::default())
+	}
+}
+
+/// Charges transaction fees in the native currency, or in `asset_id` if it is set.
+///
+/// A drop-in replacement for `ChargeTransactionPayment`, with the same tip in native units.
+#[derive(Encode, Decode, Clone, Eq, PartialEq)]
+pub struct ChargeAssetTxPayment<T: Config> {
+	#[codec(compact)]
+	tip: BalanceOf<T>,
+	asset_id: Option<T::AssetId>,
+}
+
+impl<T: Config> ChargeAssetTxPayment<T> {
+	/// Pay `tip` on top of the fee, in `asset_id` or in the native currency.
+	pub fn new(tip: BalanceOf<T>, asset_id: Option<T::AssetId>) -> Self {
+		Self { tip, asset_id }
+	}
+
+	/// The asset fees are paid in, if not the native currency.
+	pub fn asset_id(&self) -> Option<T::AssetId> {
+		self.asset_id
+	}
+}
+
+impl<T: Config> From<BalanceOf<T>> for ChargeAssetTxPayment<T> {
+	/// Pay `tip` on top of the fee, in the native currency.
+	fn from(tip: BalanceOf<T>) -> Self {
+		Self::new(tip, None)
+	}
+}
+
+impl<T: Config> sp_std::fmt::Debug for ChargeAssetTxPayment<T> {
+	#[cfg(feature = "std")]
+	fn fmt(&self, f: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		write!(f, "ChargeAssetTxPayment<{:?}, {:?}>", self.tip, self.asset_id)
+	}
+	#[cfg(not(feature = "std"))]
+	fn fmt(&self, _: &mut sp_std::fmt::Formatter) -> sp_std::fmt::Result {
+		Ok(())
+	}
+}
+
+impl<T: Config> ChargeAssetTxPayment<T> where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+	fn native(&self) -> ChargeTransactionPayment<T> {
+		ChargeTransactionPayment::from(self.tip)
+	}
+
+	/// Move the fee of the transaction, converted to `asset`, from `who` to `FeeCollector`.
+	///
+	/// Fails for accounts without providers. Returns the conversion rate, the fee in `asset` and
+	/// the native fee.
+	fn withdraw_asset_fee(
+		&self,
+		who: &T::AccountId,
+		asset: T::AssetId,
+		info: &DispatchInfoOf<T::Call>,
+		len: usize,
+	) -> Result<(FixedU128, T::AssetBalance, BalanceOf<T>), TransactionValidityError> {
+		if frame_system::Module::<T>::providers(who) == 0 {
+			return Err(InvalidTransaction::Payment.into());
+		}
+		let rate = Pallet::<T>::conversion_rate(asset).ok_or(InvalidTransaction::Payment)?;
+		let native_fee = pallet_transaction_payment::Module::<T>::compute_fee(len as u32, info, self.tip);
+		let fee = Pallet::<T>::convert(rate, native_fee);
+		if !fee.is_zero() {
+			T::Assets::transfer(asset, who, &T::FeeCollector::get(), fee)
+				.map_err(|_| InvalidTransaction::Payment)?;
+		}
+		Ok((rate, fee, native_fee))
+	}
+}
+
+impl<T: Config> SignedExtension for ChargeAssetTxPayment<T> where
+	BalanceOf<T>: Send + Sync + From<u64> + FixedPointOperand,
+	T::Call: Dispatchable<Info = DispatchInfo, PostInfo = PostDispatchInfo>,
+{
+	const IDENTIFIER: &'static str = "ChargeAssetTxPayment";
+	type AccountId = T::AccountId;
+	type Call = T::Call;
+	type AdditionalSigned = ();
+	type Pre = (
+		// who paid the fee
+		Self::AccountId,
+		InitialPayment<
+			T::AssetId,
+			T::AssetBalance,
+			BalanceOf<T>,
+			<ChargeTransactionPayment<T> as SignedExtension>::Pre,
+		>,
+	);
+
+	fn additional_signed(&self) -> Result<(), TransactionValidityError> {
+		Ok(())
+	}
+
+	fn validate(
+		&self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> TransactionValidity {
+		match self.asset_id {
+			None => self.native().validate(who, call, info, len),
+			Some(asset) => {
+				let (_, _, native_fee) = self.withdraw_asset_fee(who, asset, info, len)?;
+				// Prioritize by the native value of the fee, like native payments.
+				Ok(ValidTransaction {
+					priority: native_fee.saturated_into(),
+					..Default::default()
+				})
+			},
+		}
+	}
+
+	fn pre_dispatch(
+		self,
+		who: &Self::AccountId,
+		call: &Self::Call,
+		info: &DispatchInfoOf<Self::Call>,
+		len: usize,
+	) -> Result<Self::Pre, TransactionValidityError> {
+		let payment = match self.asset_id {
+			None => InitialPayment::Native(self.native().pre_dispatch(who, call, info, len)?),
+			Some(asset) => {
+				let (rate, fee, _) = self.withdraw_asset_fee(who, asset, info, len)?;
+				InitialPayment::Asset { asset, rate, fee, tip: self.tip }
+			},
+		};
+		Ok((who.clone(), payment))
+	}
+
+	fn post_dispatch(
+		pre: Self::Pre,
+		info: &DispatchInfoOf<Self::Call>,
+		post_info: &PostDispatchInfoOf<Self::Call>,
+		len: usize,
+		result: &DispatchResult,
+	) -> Result<(), TransactionValidityError> {
+		let (who, payment) = pre;
+		let (asset, rate, paid, tip) = match payment {
+			InitialPayment::Native(pre) =>
+				return ChargeTransactionPayment::<T>::post_dispatch(pre, info, post_info, len, result),
+			InitialPayment::Asset { asset, rate, fee, tip } => (asset, rate, fee, tip),
+		};
+
+		let actual_fee = pallet_transaction_payment::Module::<T>::compute_actual_fee(
+			len as u32,
+			info,
+			post_info,
+			tip,
+		);
+		// The rate is the one the fee was paid at, even if the call changed it.
+		let refund = paid.saturating_sub(Pallet::<T>::convert(rate, actual_fee));
+		let refunded = !refund.is_zero() &&
+			T::Assets::transfer(asset, &T::FeeCollector::get(), &who, refund).is_ok();
+		let fee = if refunded { paid - refund } else { paid };
+		Pallet::<T>::deposit_fee_paid(who, asset, fee);
+		Ok(())
+	}
+}
@@ -0,0 +1,149 @@
+use crate as pallet_asset_tx_payment;
+use std::{cell::RefCell, collections::BTreeMap};
+use sp_core::H256;
+use frame_support::{
+	parameter_types,
+	weights::{DispatchClass, IdentityFee},
+};
+use frame_system::limits;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	testing::Header,
+	DispatchError, DispatchResult,
+};
+use frame_system as system;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
+		TransactionPayment: pallet_transaction_payment::{Module, Storage},
+		AssetTxPayment: pallet_asset_tx_payment::{Module, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+	// No base fees, so fees are easy to compute in tests.
+	pub BlockWeights: limits::BlockWeights = limits::BlockWeights::builder()
+		.base_block(0)
+		.for_class(DispatchClass::all(), |weights| weights.base_extrinsic = 0)
+		.build_or_panic();
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = BlockWeights;
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const TransactionByteFee: u64 = 1;
+}
+
+impl pallet_transaction_payment::Config for Test {
+	type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, ()>;
+	type TransactionByteFee = TransactionByteFee;
+	type WeightToFee = IdentityFee<u64>;
+	type FeeMultiplierUpdate = ();
+}
+
+thread_local! {
+	pub static ASSETS: RefCell<BTreeMap<(u32, u64), u64>> = RefCell::new(BTreeMap::new());
+}
+
+/// A ledger of asset balances in `ASSETS`.
+pub struct TestAssets;
+
+impl crate::AssetTransfer<u64, u32, u64> for TestAssets {
+	fn transfer(asset: u32, from: &u64, to: &u64, amount: u64) -> DispatchResult {
+		ASSETS.with(|assets| {
+			let mut assets = assets.borrow_mut();
+			let balance = assets.entry((asset, *from)).or_default();
+			*balance = balance.checked_sub(amount).ok_or(DispatchError::Other("insufficient balance"))?;
+			*assets.entry((asset, *to)).or_default() += amount;
+			Ok(())
+		})
+	}
+}
+
+/// The balance of `asset` held by `who`.
+pub fn asset_balance(asset: u32, who: u64) -> u64 {
+	ASSETS.with(|assets| assets.borrow().get(&(asset, who)).copied().unwrap_or_default())
+}
+
+parameter_types! {
+	pub const FeeCollector: u64 = 99;
+}
+
+impl pallet_asset_tx_payment::Config for Test {
+	type Event = Event;
+	type AssetId = u32;
+	type AssetBalance = u64;
+	type Assets = TestAssets;
+	type FeeCollector = FeeCollector;
+	type RateOrigin = frame_system::EnsureRoot<u64>;
+	type WeightInfo = ();
+}
+
+/// The asset held by [`ENDOWED`] accounts in [`new_test_ext`].
+pub const ASSET: u32 = 7;
+pub const ENDOWED: [u64; 2] = [1, 2];
+pub const ENDOWMENT: u64 = 1_000;
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: ENDOWED.iter().map(|&who| (who, ENDOWMENT)).collect(),
+	}.assimilate_storage(&mut t).unwrap();
+	ASSETS.with(|assets| {
+		*assets.borrow_mut() = ENDOWED.iter().map(|&who| ((ASSET, who), ENDOWMENT)).collect();
+	});
+	let mut ext = sp_io::TestExternalities::new(t);
+	// Events are not deposited in the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
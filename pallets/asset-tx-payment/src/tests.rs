@@ -0,0 +1,165 @@
+use crate::{ChargeAssetTxPayment, Event as AssetTxPaymentEvent, mock::*};
+use frame_support::{
+	assert_noop, assert_ok,
+	weights::{DispatchInfo, PostDispatchInfo, Pays},
+};
+use pallet_transaction_payment::{FeeDetails, InclusionFee};
+use sp_runtime::{
+	traits::SignedExtension,
+	transaction_validity::InvalidTransaction,
+	DispatchError::BadOrigin,
+	FixedPointNumber, FixedU128,
+};
+
+fn last_event() -> Event {
+	System::events().pop().expect("an event was deposited").event
+}
+
+fn call() -> Call {
+	Call::System(frame_system::Call::remark(vec![]))
+}
+
+/// Dispatch info of a call weighing 100.
+fn info() -> DispatchInfo {
+	DispatchInfo { weight: 100, ..Default::default() }
+}
+
+/// Post dispatch info of a call that turned out to weigh 50.
+fn post_info() -> PostDispatchInfo {
+	PostDispatchInfo { actual_weight: Some(50), pays_fee: Pays::Yes }
+}
+
+const LEN: usize = 10;
+
+fn set_rate(numerator: u64, denominator: u64) {
+	let rate = FixedU128::saturating_from_rational(numerator, denominator);
+	assert_ok!(AssetTxPayment::set_conversion_rate(Origin::root(), ASSET, Some(rate)));
+}
+
+#[test]
+fn conversion_rates_are_set_by_the_rate_origin() {
+	new_test_ext().execute_with(|| {
+		let rate = FixedU128::saturating_from_integer(2);
+		assert_noop!(AssetTxPayment::set_conversion_rate(Origin::signed(1), ASSET, Some(rate)), BadOrigin);
+
+		assert_ok!(AssetTxPayment::set_conversion_rate(Origin::root(), ASSET, Some(rate)));
+		assert_eq!(AssetTxPayment::conversion_rate(ASSET), Some(rate));
+		assert_eq!(
+			last_event(),
+			Event::pallet_asset_tx_payment(AssetTxPaymentEvent::ConversionRateSet(ASSET, Some(rate))),
+		);
+
+		assert_ok!(AssetTxPayment::set_conversion_rate(Origin::root(), ASSET, None));
+		assert_eq!(AssetTxPayment::conversion_rate(ASSET), None);
+	});
+}
+
+#[test]
+fn fees_are_paid_natively_without_an_asset() {
+	new_test_ext().execute_with(|| {
+		let pre = ChargeAssetTxPayment::<Test>::from(5)
+			.pre_dispatch(&1, &call(), &info(), LEN)
+			.unwrap();
+		// Length, weight and tip.
+		assert_eq!(Balances::free_balance(1), ENDOWMENT - 10 - 100 - 5);
+
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(pre, &info(), &post_info(), LEN, &Ok(())));
+		assert_eq!(Balances::free_balance(1), ENDOWMENT - 10 - 50 - 5);
+		assert_eq!(asset_balance(ASSET, 1), ENDOWMENT);
+	});
+}
+
+#[test]
+fn fees_are_paid_in_the_asset_at_its_rate() {
+	new_test_ext().execute_with(|| {
+		set_rate(2, 1);
+
+		let pre = ChargeAssetTxPayment::<Test>::new(5, Some(ASSET))
+			.pre_dispatch(&1, &call(), &info(), LEN)
+			.unwrap();
+		assert_eq!(asset_balance(ASSET, 1), ENDOWMENT - 2 * 115);
+		assert_eq!(asset_balance(ASSET, FeeCollector::get()), 2 * 115);
+		assert_eq!(Balances::free_balance(1), ENDOWMENT);
+
+		// The unused weight is refunded in the asset.
+		assert_ok!(ChargeAssetTxPayment::<Test>::post_dispatch(pre, &info(), &post_info(), LEN, &Ok(())));
+		assert_eq!(asset_balance(ASSET, 1), ENDOWMENT - 2 * 65);
+		assert_eq!(asset_balance(ASSET, FeeCollector::get()), 2 * 65);
+		assert_eq!(
+			last_event(),
+			Event::pallet_asset_tx_payment(AssetTxPaymentEvent::AssetTxFeePaid(1, ASSET, 2 * 65)),
+		);
+	});
+}
+
+#[test]
+fn asset_fees_are_rounded_down() {
+	new_test_ext().execute_with(|| {
+		set_rate(1, 3);
+
+		assert_ok!(ChargeAssetTxPayment::<Test>::new(0, Some(ASSET)).pre_dispatch(&1, &call(), &info(), LEN));
+		assert_eq!(asset_balance(ASSET, 1), ENDOWMENT - 110 / 3);
+	});
+}
+
+#[test]
+fn assets_without_a_rate_cannot_pay_fees() {
+	new_test_ext().execute_with(|| {
+		let charge = ChargeAssetTxPayment::<Test>::new(0, Some(ASSET));
+		assert_eq!(charge.validate(&1, &call(), &info(), LEN), InvalidTransaction::Payment.into());
+
+		set_rate(1, 1);
+		assert!(charge.validate(&1, &call(), &info(), LEN).is_ok());
+	});
+}
+
+#[test]
+fn asset_fees_must_be_affordable() {
+	new_test_ext().execute_with(|| {
+		set_rate(100, 1);
+
+		let charge = ChargeAssetTxPayment::<Test>::new(0, Some(ASSET));
+		assert_eq!(charge.validate(&1, &call(), &info(), LEN), InvalidTransaction::Payment.into());
+		// Account 3 holds no asset at all.
+		set_rate(1, 1);
+		assert_eq!(charge.validate(&3, &call(), &info(), LEN), InvalidTransaction::Payment.into());
+	});
+}
+
+#[test]
+fn accounts_without_providers_cannot_pay_in_assets() {
+	new_test_ext().execute_with(|| {
+		set_rate(1, 1);
+		// Account 3 holds the asset, but no native balance.
+		ASSETS.with(|assets| assets.borrow_mut().insert((ASSET, 3), ENDOWMENT));
+
+		let charge = ChargeAssetTxPayment::<Test>::new(0, Some(ASSET));
+		assert_eq!(charge.validate(&3, &call(), &info(), LEN), InvalidTransaction::Payment.into());
+		assert!(charge.clone().pre_dispatch(&3, &call(), &info(), LEN).is_err());
+		assert_eq!(asset_balance(ASSET, 3), ENDOWMENT);
+
+		assert_ok!(Balances::transfer(Origin::signed(1), 3, 1));
+		assert!(charge.validate(&3, &call(), &info(), LEN).is_ok());
+	});
+}
+
+#[test]
+fn fee_details_are_converted_to_the_asset() {
+	new_test_ext().execute_with(|| {
+		let details = FeeDetails {
+			inclusion_fee: Some(InclusionFee { base_fee: 1, len_fee: 10, adjusted_weight_fee: 100 }),
+			tip: 5,
+		};
+		assert_eq!(AssetTxPayment::to_asset_fee_details(ASSET, details.clone()), None);
+
+		set_rate(3, 1);
+		assert_eq!(AssetTxPayment::to_asset_balance(ASSET, 116), Some(348));
+		assert_eq!(
+			AssetTxPayment::to_asset_fee_details(ASSET, details),
+			Some(FeeDetails {
+				inclusion_fee: Some(InclusionFee { base_fee: 3, len_fee: 30, adjusted_weight_fee: 300 }),
+				tip: 15,
+			}),
+		);
+	});
+}
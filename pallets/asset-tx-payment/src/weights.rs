@@ -0,0 +1,44 @@
+//! Weights for pallet_asset_tx_payment
+//!
+//! These values are estimates, not benchmark results. Each call is charged one
+//! `ExtrinsicBaseWeight` for its execution, plus the database reads and writes it makes, priced
+//! by `DbWeight`. Replace them with the output of
+//!
+//! ```text
+//! ./target/release/node-template benchmark --chain=dev --steps=50 --repeat=20 \
+//!     --pallet=pallet_asset_tx_payment --extrinsic=* --execution=wasm --wasm-execution=compiled \
+//!     --heap-pages=4096 --output=./pallets/asset-tx-payment/src/weights.rs
+//! ```
+//!
+//! on reference hardware before relying on them.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{Weight, constants::{ExtrinsicBaseWeight, RocksDbWeight}},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_asset_tx_payment.
+pub trait WeightInfo {
+	fn set_conversion_rate() -> Weight;
+}
+
+/// Weights for pallet_asset_tx_payment using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn set_conversion_rate() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn set_conversion_rate() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+}
@@ -0,0 +1,16 @@
+//! Runtime API definition for the Aura equivocation pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_runtime::traits::Block as BlockT;
+
+sp_api::decl_runtime_apis! {
+	pub trait AuraEquivocationApi {
+		/// Submit an unsigned extrinsic reporting that `first` and `second` were sealed by the
+		/// same author in the same slot. Returns `None` if it could not be submitted.
+		fn submit_report_equivocation_unsigned_extrinsic(
+			first: <Block as BlockT>::Header,
+			second: <Block as BlockT>::Header,
+		) -> Option<()>;
+	}
+}
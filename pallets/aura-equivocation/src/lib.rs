@@ -0,0 +1,276 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Aura Equivocation Pallet
+//!
+//! Aura authors take turns: the author of a slot is `authorities[slot % authorities.len()]`, and
+//! it seals the one block it authors in that slot. An author that seals two different blocks for
+//! the same slot equivocates. The node notices this while importing blocks and reports both
+//! headers with the unsigned `report_equivocation_unsigned` extrinsic, through the
+//! `AuraEquivocationApi` runtime API.
+//!
+//! The report is checked against the current `pallet_aura` authorities: both headers must carry
+//! the same slot and a valid seal of that slot's author. A valid report is recorded in `Reports`
+//! and an [`AuraEquivocationOffence`] is reported against the author through `ReportOffence`,
+//! e.g. `pallet_offences`, whose `OnOffenceHandler` decides the penalty.
+//!
+//! Since only the current authorities are known, equivocations have to be reported in the session
+//! they happened in.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+use codec::Decode;
+use sp_consensus_aura::AURA_ENGINE_ID;
+use sp_runtime::{
+	traits::Header as HeaderT,
+	DigestItem, Perbill, RuntimeDebug,
+};
+use sp_staking::{
+	offence::{Kind, Offence},
+	SessionIndex,
+};
+use sp_std::prelude::*;
+
+/// The slot `header` was authored in, read from its Aura pre-runtime digest.
+pub fn slot_of<H: HeaderT>(header: &H) -> Option<u64> {
+	header.digest().logs().iter().find_map(|item| match item {
+		DigestItem::PreRuntime(id, data) if id == &AURA_ENGINE_ID =>
+			u64::decode(&mut &data[..]).ok(),
+		_ => None,
+	})
+}
+
+/// An Aura authority sealed two blocks in one slot.
+#[derive(Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct AuraEquivocationOffence<Offender> {
+	/// The slot of the two blocks.
+	pub slot: u64,
+	/// The session the slot is in.
+	pub session_index: SessionIndex,
+	/// The number of validators in the session.
+	pub validator_set_count: u32,
+	/// The author of the two blocks.
+	pub offender: Offender,
+}
+
+impl<Offender: Clone> Offence<Offender> for AuraEquivocationOffence<Offender> {
+	const ID: Kind = *b"aura:equivocatio";
+	type TimeSlot = u64;
+
+	fn offenders(&self) -> Vec<Offender> {
+		vec![self.offender.clone()]
+	}
+
+	fn session_index(&self) -> SessionIndex {
+		self.session_index
+	}
+
+	fn validator_set_count(&self) -> u32 {
+		self.validator_set_count
+	}
+
+	fn time_slot(&self) -> Self::TimeSlot {
+		self.slot
+	}
+
+	/// The same as for BABE and GRANDPA equivocations: `min(1, 3k / n)^2` for `k` offenders
+	/// out of `n` validators.
+	fn slash_fraction(offenders_count: u32, validator_set_count: u32) -> Perbill {
+		let x = Perbill::from_rational_approximation(3 * offenders_count, validator_set_count);
+		x.square()
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+	use frame_system::{pallet_prelude::*, offchain::{SendTransactionTypes, SubmitTransaction}};
+	use sp_runtime::{
+		traits::{Hash, Header as HeaderT},
+		DigestItem, RuntimeAppPublic,
+	};
+	use sp_consensus_aura::AURA_ENGINE_ID;
+	use sp_staking::offence::ReportOffence;
+	use sp_std::prelude::*;
+	use authority_identification::IdentifyAuthority;
+	use super::{slot_of, AuraEquivocationOffence, WeightInfo};
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config + pallet_aura::Config + SendTransactionTypes<Call<Self>>
+	{
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The identification of an offending validator, as reported to `ReportOffence`.
+		type Offender: Clone;
+		/// Identifies the validator behind an equivocating authority.
+		type IdentifyAuthority: IdentifyAuthority<Self::Offender>;
+		/// Where equivocations are reported, e.g. `pallet_offences`.
+		type ReportOffence: ReportOffence<
+			Self::AccountId,
+			Self::Offender,
+			AuraEquivocationOffence<Self::Offender>,
+		>;
+		/// Number of blocks a report stays valid in the transaction pool.
+		#[pallet::constant]
+		type ReportLongevity: Get<u64>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The index of the authority that equivocated in each reported slot.
+	#[pallet::storage]
+	#[pallet::getter(fn reports)]
+	pub type Reports<T> = StorageMap<_, Twox64Concat, u64, u32>;
+
+	#[pallet::event]
+	#[pallet::metadata(T::AuthorityId = "AuthorityId")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An authority sealed two blocks in one slot. [authority, slot]
+		EquivocationReported(T::AuthorityId, u64),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// A header has no Aura slot.
+		MissingSlot,
+		/// The headers are from different slots.
+		SlotMismatch,
+		/// The two headers are the same.
+		IdenticalHeaders,
+		/// A header is not sealed by the author of its slot.
+		InvalidSeal,
+		/// An equivocation was already reported for this slot.
+		DuplicateReport,
+		/// The author of the slot is not a validator of the current session.
+		UnknownOffender,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Report that the author of `first` and `second` sealed both in the same slot.
+		///
+		/// This is an unsigned transaction, only accepted from the local node or in blocks; see
+		/// `validate_unsigned`.
+		#[pallet::weight(T::WeightInfo::report_equivocation())]
+		pub fn report_equivocation_unsigned(
+			origin: OriginFor<T>,
+			first: T::Header,
+			second: T::Header,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			let (slot, index, author) = Self::check_equivocation(&first, &second)?;
+			ensure!(!<Reports<T>>::contains_key(slot), Error::<T>::DuplicateReport);
+			let (offender, session_index, validator_set_count) =
+				T::IdentifyAuthority::identify(index).ok_or(Error::<T>::UnknownOffender)?;
+
+			let offence = AuraEquivocationOffence { slot, session_index, validator_set_count, offender };
+			// Reports are unsigned, so there is no reporter to reward.
+			T::ReportOffence::report_offence(Vec::new(), offence)
+				.map_err(|_| Error::<T>::DuplicateReport)?;
+			<Reports<T>>::insert(slot, index);
+
+			Self::deposit_event(Event::EquivocationReported(author, slot));
+			Ok(().into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if let Call::report_equivocation_unsigned(first, second) = call {
+				// Reports are only made by the local node, and must not be spammed through the
+				// network.
+				match source {
+					TransactionSource::Local | TransactionSource::InBlock => {},
+					_ => return InvalidTransaction::Call.into(),
+				}
+
+				let (slot, _, _) = Self::check_equivocation(first, second)
+					.map_err(|_| InvalidTransaction::BadProof)?;
+				if <Reports<T>>::contains_key(slot) {
+					return InvalidTransaction::Stale.into();
+				}
+
+				ValidTransaction::with_tag_prefix("AuraEquivocation")
+					// Prioritize equivocation reports above other transactions.
+					.priority(TransactionPriority::max_value())
+					// Only one report per slot can make it into the pool.
+					.and_provides(slot)
+					.longevity(T::ReportLongevity::get())
+					.propagate(false)
+					.build()
+			} else {
+				InvalidTransaction::Call.into()
+			}
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Submit a report of `first` and `second` to the transaction pool.
+		///
+		/// Meant to be called from the runtime API, with the transaction pool extension available.
+		pub fn submit_unsigned_equivocation_report(first: T::Header, second: T::Header) -> Option<()> {
+			let call = Call::report_equivocation_unsigned(first, second);
+			SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into()).ok()
+		}
+
+		/// Check that `first` and `second` are an equivocation, returning their slot and the index
+		/// and key of the author.
+		pub fn check_equivocation(
+			first: &T::Header,
+			second: &T::Header,
+		) -> Result<(u64, u32, T::AuthorityId), Error<T>> {
+			let slot = slot_of(first).ok_or(Error::<T>::MissingSlot)?;
+			ensure!(slot_of(second) == Some(slot), Error::<T>::SlotMismatch);
+			ensure!(first.hash() != second.hash(), Error::<T>::IdenticalHeaders);
+
+			let authorities = <pallet_aura::Module<T>>::authorities();
+			if authorities.is_empty() {
+				return Err(Error::<T>::InvalidSeal);
+			}
+			let index = (slot % authorities.len() as u64) as usize;
+			let author = authorities[index].clone();
+
+			ensure!(Self::is_sealed_by(first.clone(), &author), Error::<T>::InvalidSeal);
+			ensure!(Self::is_sealed_by(second.clone(), &author), Error::<T>::InvalidSeal);
+			Ok((slot, index as u32, author))
+		}
+
+		/// Whether the last digest item of `header` is a seal by `author`.
+		///
+		/// The seal signs the hash of the header without the seal itself.
+		fn is_sealed_by(mut header: T::Header, author: &T::AuthorityId) -> bool {
+			let seal = match header.digest_mut().pop() {
+				Some(DigestItem::Seal(id, seal)) if id == AURA_ENGINE_ID => seal,
+				_ => return false,
+			};
+			let signature = match <T::AuthorityId as RuntimeAppPublic>::Signature::decode(&mut &seal[..]) {
+				Ok(signature) => signature,
+				Err(_) => return false,
+			};
+
+			let pre_hash = T::Hashing::hash_of(&header);
+			author.verify(&pre_hash, &signature)
+		}
+	}
+}
@@ -0,0 +1,155 @@
+use crate as pallet_aura_equivocation;
+use crate::AuraEquivocationOffence;
+use authority_identification::IdentifyAuthority;
+use std::cell::RefCell;
+use sp_core::H256;
+use frame_support::parameter_types;
+use sp_consensus_aura::sr25519::AuthorityId;
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::{
+	traits::{BlakeTwo256, IdentityLookup},
+	testing::Header,
+};
+use sp_staking::{offence::{OffenceError, ReportOffence}, SessionIndex};
+use frame_system as system;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Module, Call, Config, Storage, Event<T>},
+		Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
+		Aura: pallet_aura::{Module, Config<T>},
+		AuraEquivocation: pallet_aura_equivocation::{Module, Call, Storage, Event<T>, ValidateUnsigned},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+impl system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+}
+
+parameter_types! {
+	pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = ();
+	type MinimumPeriod = MinimumPeriod;
+	type WeightInfo = ();
+}
+
+impl pallet_aura::Config for Test {
+	type AuthorityId = AuthorityId;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Test where Call: From<C> {
+	type OverarchingCall = Call;
+	type Extrinsic = UncheckedExtrinsic;
+}
+
+/// The session index of the mock.
+pub const SESSION: SessionIndex = 7;
+
+thread_local! {
+	pub static OFFENCES: RefCell<Vec<AuraEquivocationOffence<u64>>> = RefCell::new(vec![]);
+}
+
+/// Identifies the authority at `index` as the account `index`, in session `SESSION`.
+pub struct IndexAsAccount;
+
+impl IdentifyAuthority<u64> for IndexAsAccount {
+	fn identify(index: u32) -> Option<(u64, SessionIndex, u32)> {
+		if (index as usize) < AUTHORITIES.len() {
+			Some((index as u64, SESSION, AUTHORITIES.len() as u32))
+		} else {
+			None
+		}
+	}
+}
+
+/// Records the offences reported by the pallet in `OFFENCES`.
+pub struct RecordOffences;
+
+impl ReportOffence<u64, u64, AuraEquivocationOffence<u64>> for RecordOffences {
+	fn report_offence(
+		_reporters: Vec<u64>,
+		offence: AuraEquivocationOffence<u64>,
+	) -> Result<(), OffenceError> {
+		OFFENCES.with(|o| o.borrow_mut().push(offence));
+		Ok(())
+	}
+
+	fn is_known_offence(offenders: &[u64], time_slot: &u64) -> bool {
+		OFFENCES.with(|o| o.borrow().iter().any(|offence| {
+			&offence.slot == time_slot && offenders.contains(&offence.offender)
+		}))
+	}
+}
+
+/// The offences reported so far.
+pub fn offences() -> Vec<AuraEquivocationOffence<u64>> {
+	OFFENCES.with(|o| o.borrow().clone())
+}
+
+parameter_types! {
+	pub const ReportLongevity: u64 = 100;
+}
+
+impl pallet_aura_equivocation::Config for Test {
+	type Event = Event;
+	type Offender = u64;
+	type IdentifyAuthority = IndexAsAccount;
+	type ReportOffence = RecordOffences;
+	type ReportLongevity = ReportLongevity;
+	type WeightInfo = ();
+}
+
+/// The Aura authorities of the mock, in order: Alice authors slots 0, 3, 6, ...
+pub const AUTHORITIES: [Sr25519Keyring; 3] =
+	[Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie];
+
+// Build genesis storage according to the mock runtime.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_aura::GenesisConfig::<Test> {
+		authorities: AUTHORITIES.iter().map(|k| k.public().into()).collect(),
+	}.assimilate_storage(&mut t).unwrap();
+	OFFENCES.with(|o| o.borrow_mut().clear());
+	let mut ext = sp_io::TestExternalities::new(t);
+	// Events are not deposited in the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,154 @@
+use crate::{AuraEquivocationOffence, Error, Event as AuraEquivocationEvent, mock::*};
+use codec::Encode;
+use frame_support::{assert_noop, assert_ok, unsigned::ValidateUnsigned};
+use sp_consensus_aura::AURA_ENGINE_ID;
+use sp_core::H256;
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::{
+	testing::Header,
+	traits::Header as _,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	Digest, DigestItem, Perbill,
+};
+use sp_staking::offence::Offence;
+
+/// A header for `slot`, distinguished by `state_root` and sealed by `author`.
+fn sealed_header(slot: u64, state_root: u8, author: Sr25519Keyring) -> Header {
+	let digest = Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())] };
+	let mut header = Header::new(1, H256::zero(), H256::repeat_byte(state_root), H256::zero(), digest);
+	let signature = author.sign(header.hash().as_ref());
+	header.digest_mut().push(DigestItem::Seal(AURA_ENGINE_ID, signature.encode()));
+	header
+}
+
+fn report(first: Header, second: Header) -> frame_support::dispatch::DispatchResultWithPostInfo {
+	AuraEquivocation::report_equivocation_unsigned(Origin::none(), first, second)
+}
+
+#[test]
+fn slot_is_read_from_the_pre_runtime_digest() {
+	assert_eq!(crate::slot_of(&sealed_header(42, 0, Sr25519Keyring::Alice)), Some(42));
+	assert_eq!(crate::slot_of(&Header::new(1, H256::zero(), H256::zero(), H256::zero(), Default::default())), None);
+}
+
+#[test]
+fn report_equivocation_works() {
+	new_test_ext().execute_with(|| {
+		// Bob authors slot 4 of 3 authorities.
+		let first = sealed_header(4, 1, Sr25519Keyring::Bob);
+		let second = sealed_header(4, 2, Sr25519Keyring::Bob);
+
+		assert_ok!(report(first, second));
+		assert_eq!(AuraEquivocation::reports(4), Some(1));
+		assert_eq!(
+			offences(),
+			vec![AuraEquivocationOffence { slot: 4, session_index: SESSION, validator_set_count: 3, offender: 1 }],
+		);
+		assert_eq!(
+			System::events().pop().expect("an event was deposited").event,
+			Event::pallet_aura_equivocation(AuraEquivocationEvent::EquivocationReported(
+				Sr25519Keyring::Bob.public().into(),
+				4,
+			)),
+		);
+	});
+}
+
+#[test]
+fn a_slot_is_only_reported_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(report(sealed_header(3, 1, Sr25519Keyring::Alice), sealed_header(3, 2, Sr25519Keyring::Alice)));
+		assert_noop!(
+			report(sealed_header(3, 1, Sr25519Keyring::Alice), sealed_header(3, 3, Sr25519Keyring::Alice)),
+			Error::<Test>::DuplicateReport,
+		);
+		assert_eq!(offences().len(), 1);
+		assert_eq!(offences()[0].offender, 0);
+	});
+}
+
+#[test]
+fn invalid_reports_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let unsealed = Header::new(1, H256::zero(), H256::zero(), H256::zero(), Default::default());
+		assert_noop!(
+			report(unsealed, sealed_header(0, 1, Sr25519Keyring::Alice)),
+			Error::<Test>::MissingSlot,
+		);
+		assert_noop!(
+			report(sealed_header(0, 1, Sr25519Keyring::Alice), sealed_header(3, 2, Sr25519Keyring::Alice)),
+			Error::<Test>::SlotMismatch,
+		);
+		assert_noop!(
+			report(sealed_header(0, 1, Sr25519Keyring::Alice), sealed_header(0, 1, Sr25519Keyring::Alice)),
+			Error::<Test>::IdenticalHeaders,
+		);
+		// Slot 1 belongs to Bob, not Alice.
+		assert_noop!(
+			report(sealed_header(1, 1, Sr25519Keyring::Alice), sealed_header(1, 2, Sr25519Keyring::Alice)),
+			Error::<Test>::InvalidSeal,
+		);
+		// Both headers must be sealed by the slot's author.
+		assert_noop!(
+			report(sealed_header(1, 1, Sr25519Keyring::Bob), sealed_header(1, 2, Sr25519Keyring::Charlie)),
+			Error::<Test>::InvalidSeal,
+		);
+		assert!(offences().is_empty());
+	});
+}
+
+#[test]
+fn slash_fraction_grows_with_the_share_of_offenders() {
+	type AuraOffence = AuraEquivocationOffence<u64>;
+	assert_eq!(AuraOffence::slash_fraction(1, 30), Perbill::from_percent(1));
+	assert_eq!(AuraOffence::slash_fraction(1, 3), Perbill::one());
+	assert_eq!(AuraOffence::slash_fraction(5, 30), Perbill::from_percent(25));
+}
+
+#[test]
+fn reports_must_be_unsigned() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			AuraEquivocation::report_equivocation_unsigned(
+				Origin::signed(1),
+				sealed_header(0, 1, Sr25519Keyring::Alice),
+				sealed_header(0, 2, Sr25519Keyring::Alice),
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_accepts_only_local_fresh_reports() {
+	new_test_ext().execute_with(|| {
+		let call = crate::Call::report_equivocation_unsigned(
+			sealed_header(2, 1, Sr25519Keyring::Charlie),
+			sealed_header(2, 2, Sr25519Keyring::Charlie),
+		);
+
+		assert_eq!(
+			AuraEquivocation::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into(),
+		);
+		let valid = AuraEquivocation::validate_unsigned(TransactionSource::Local, &call)
+			.expect("a valid report is accepted");
+		assert_eq!(valid.provides, vec![("AuraEquivocation", 2u64).encode()]);
+		assert!(!valid.propagate);
+
+		let bad = crate::Call::report_equivocation_unsigned(
+			sealed_header(2, 1, Sr25519Keyring::Alice),
+			sealed_header(2, 2, Sr25519Keyring::Alice),
+		);
+		assert_eq!(
+			AuraEquivocation::validate_unsigned(TransactionSource::Local, &bad),
+			InvalidTransaction::BadProof.into(),
+		);
+
+		assert_ok!(report(sealed_header(2, 1, Sr25519Keyring::Charlie), sealed_header(2, 2, Sr25519Keyring::Charlie)));
+		assert_eq!(
+			AuraEquivocation::validate_unsigned(TransactionSource::Local, &call),
+			InvalidTransaction::Stale.into(),
+		);
+	});
+}
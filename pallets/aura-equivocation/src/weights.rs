@@ -0,0 +1,36 @@
+//! Weights for pallet_aura_equivocation
+//!
+//! The report cannot be benchmarked with the pallet alone, since it needs two headers sealed by a
+//! current Aura authority. These weights are estimated from the cost of verifying two sr25519
+//! seals plus the storage accessed by the call, by `pallet_offences` when the offence is reported
+//! and by an `OnOffenceHandler` removing the offender, such as `pallet_validator_set`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_aura_equivocation.
+pub trait WeightInfo {
+	fn report_equivocation() -> Weight;
+}
+
+/// Weights for pallet_aura_equivocation using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn report_equivocation() -> Weight {
+		(112_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(10 as Weight))
+			.saturating_add(T::DbWeight::get().writes(8 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn report_equivocation() -> Weight {
+		(112_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(10 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(8 as Weight))
+	}
+}
@@ -0,0 +1,61 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Identity Registrars Pallet
+//!
+//! Sets up the identity registrars of `pallet_identity` from the genesis config, which that
+//! pallet cannot do itself. The registrars are added in order, so the first one gets index `0`.
+//!
+//! Registrars are added with the `Root` origin, so `pallet_identity`'s `RegistrarOrigin` must
+//! accept it. The pallet has no storage and no calls; later registrars are added through
+//! `pallet_identity::add_registrar`.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::{pallet_prelude::*, RawOrigin};
+	use sp_std::prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_identity::Config {}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The identity registrars at genesis, in the order of their indices.
+		pub registrars: Vec<T::AccountId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { registrars: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for registrar in &self.registrars {
+				<pallet_identity::Module<T>>::add_registrar(RawOrigin::Root.into(), registrar.clone())
+					.expect("genesis registrars must be accepted; qed");
+			}
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {}
+}
@@ -0,0 +1,26 @@
+use crate::mock::*;
+
+#[test]
+fn genesis_adds_registrars_in_order() {
+	new_test_ext(vec![3, 1]).execute_with(|| {
+		let registrars = Identity::registrars()
+			.into_iter()
+			.map(|registrar| registrar.expect("registrars are not removed").account)
+			.collect::<Vec<_>>();
+		assert_eq!(registrars, vec![3, 1]);
+	});
+}
+
+#[test]
+fn genesis_without_registrars_adds_none() {
+	new_test_ext(vec![]).execute_with(|| {
+		assert!(Identity::registrars().is_empty());
+	});
+}
+
+#[test]
+#[should_panic(expected = "genesis registrars must be accepted")]
+fn genesis_fails_with_too_many_registrars() {
+	// The mock allows `MaxRegistrars = 2`.
+	new_test_ext(vec![1, 2, 3]);
+}
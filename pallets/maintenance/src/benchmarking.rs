@@ -0,0 +1,44 @@
+//! Benchmarking setup for pallet-maintenance
+
+use super::*;
+
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::EnsureOrigin;
+use sp_std::prelude::*;
+#[allow(unused)]
+use crate::Pallet as Maintenance;
+
+benchmarks! {
+	set_maintenance_mode {
+		let origin = T::MaintenanceOrigin::successful_origin();
+	}: _(origin, true)
+	verify {
+		assert!(Maintenance::<T>::maintenance_mode());
+	}
+
+	// Blocking a single call writes the larger key.
+	block {
+		let origin = T::MaintenanceOrigin::successful_origin();
+		let pallet = b"Balances".to_vec();
+		let call = b"transfer_keep_alive".to_vec();
+	}: _(origin, pallet.clone(), Some(call.clone()))
+	verify {
+		assert!(BlockedCalls::<T>::contains_key(&pallet, &call));
+	}
+
+	unblock {
+		let origin = T::MaintenanceOrigin::successful_origin();
+		let pallet = b"Balances".to_vec();
+		let call = b"transfer_keep_alive".to_vec();
+		BlockedCalls::<T>::insert(&pallet, &call, ());
+	}: _(origin, pallet.clone(), Some(call.clone()))
+	verify {
+		assert!(!BlockedCalls::<T>::contains_key(&pallet, &call));
+	}
+}
+
+impl_benchmark_test_suite!(
+	Maintenance,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
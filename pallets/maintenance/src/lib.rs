@@ -0,0 +1,189 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Maintenance Pallet
+//!
+//! A call filter for the runtime, meant to be its `BaseCallFilter`. It lets `MaintenanceOrigin`
+//! freeze parts of the chain during incidents without halting block production:
+//!
+//! - In maintenance mode, only the calls accepted by `SafeCalls` can be dispatched.
+//! - Outside of it, whole pallets or single calls can be blocked by name, e.g. `Balances` or
+//!   `Balances::transfer`, as they appear in the metadata.
+//!
+//! `SafeCalls` and the calls of this pallet are never filtered, so the chain can always be
+//! brought back. Note that `Root` bypasses the base call filter altogether.
+
+pub use pallet::*;
+
+use frame_support::traits::{Filter, GetCallMetadata};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		dispatch::DispatchResultWithPostInfo, pallet_prelude::*, traits::{Filter, PalletInfo as _},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_std::prelude::*;
+	use super::WeightInfo;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+		/// The origin allowed to switch maintenance mode and change the blocklist.
+		type MaintenanceOrigin: EnsureOrigin<Self::Origin>;
+		/// The calls that are never filtered, e.g. those needed to produce blocks and govern.
+		type SafeCalls: Filter<<Self as frame_system::Config>::Call>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// Whether only `SafeCalls` can be dispatched.
+	#[pallet::storage]
+	#[pallet::getter(fn maintenance_mode)]
+	pub type MaintenanceMode<T> = StorageValue<_, bool, ValueQuery>;
+
+	/// The pallets whose calls are all blocked, by name.
+	#[pallet::storage]
+	pub type BlockedPallets<T> = StorageMap<_, Blake2_128Concat, Vec<u8>, ()>;
+
+	/// The calls that are blocked, by pallet and call name.
+	#[pallet::storage]
+	pub type BlockedCalls<T> = StorageDoubleMap<_, Blake2_128Concat, Vec<u8>, Blake2_128Concat, Vec<u8>, ()>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event {
+		/// Maintenance mode was switched on or off. [enabled]
+		MaintenanceModeSet(bool),
+		/// A pallet, or one of its calls, was blocked. [pallet, call]
+		Blocked(Vec<u8>, Option<Vec<u8>>),
+		/// A pallet, or one of its calls, was unblocked. [pallet, call]
+		Unblocked(Vec<u8>, Option<Vec<u8>>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Maintenance mode is already in the requested state.
+		AlreadySet,
+		/// The pallet or call is already blocked.
+		AlreadyBlocked,
+		/// The pallet or call is not blocked.
+		NotBlocked,
+		/// The calls of this pallet cannot be blocked.
+		CannotBlockSelf,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Switch maintenance mode on or off.
+		///
+		/// The dispatch origin must be `MaintenanceOrigin`.
+		#[pallet::weight(T::WeightInfo::set_maintenance_mode())]
+		pub fn set_maintenance_mode(origin: OriginFor<T>, enabled: bool) -> DispatchResultWithPostInfo {
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+			ensure!(Self::maintenance_mode() != enabled, Error::<T>::AlreadySet);
+
+			<MaintenanceMode<T>>::put(enabled);
+			Self::deposit_event(Event::MaintenanceModeSet(enabled));
+			Ok(().into())
+		}
+
+		/// Block all calls of `pallet`, or only its call named `call`.
+		///
+		/// The dispatch origin must be `MaintenanceOrigin`.
+		#[pallet::weight(T::WeightInfo::block())]
+		pub fn block(
+			origin: OriginFor<T>,
+			pallet: Vec<u8>,
+			call: Option<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+			ensure!(!Self::is_own_pallet(&pallet), Error::<T>::CannotBlockSelf);
+			ensure!(!Self::is_blocked(&pallet, call.as_deref()), Error::<T>::AlreadyBlocked);
+
+			match &call {
+				Some(call) => <BlockedCalls<T>>::insert(&pallet, call, ()),
+				None => <BlockedPallets<T>>::insert(&pallet, ()),
+			}
+			Self::deposit_event(Event::Blocked(pallet, call));
+			Ok(().into())
+		}
+
+		/// Lift a block set with `block`.
+		///
+		/// The dispatch origin must be `MaintenanceOrigin`.
+		#[pallet::weight(T::WeightInfo::unblock())]
+		pub fn unblock(
+			origin: OriginFor<T>,
+			pallet: Vec<u8>,
+			call: Option<Vec<u8>>,
+		) -> DispatchResultWithPostInfo {
+			T::MaintenanceOrigin::ensure_origin(origin)?;
+			ensure!(Self::is_blocked(&pallet, call.as_deref()), Error::<T>::NotBlocked);
+
+			match &call {
+				Some(call) => <BlockedCalls<T>>::remove(&pallet, call),
+				None => <BlockedPallets<T>>::remove(&pallet),
+			}
+			Self::deposit_event(Event::Unblocked(pallet, call));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether `pallet`, or its call named `call`, was blocked with exactly these arguments.
+		fn is_blocked(pallet: &[u8], call: Option<&[u8]>) -> bool {
+			match call {
+				Some(call) => <BlockedCalls<T>>::contains_key(pallet, call),
+				None => <BlockedPallets<T>>::contains_key(pallet),
+			}
+		}
+
+		/// Whether `pallet` is the name of this pallet in the runtime.
+		pub(crate) fn is_own_pallet(pallet: &[u8]) -> bool {
+			<T as frame_system::Config>::PalletInfo::name::<Self>()
+				.map_or(false, |name| name.as_bytes() == pallet)
+		}
+	}
+}
+
+impl<T: Config> Filter<<T as frame_system::Config>::Call> for Pallet<T>
+where
+	<T as frame_system::Config>::Call: GetCallMetadata,
+{
+	fn filter(call: &<T as frame_system::Config>::Call) -> bool {
+		if T::SafeCalls::filter(call) {
+			return true;
+		}
+		let metadata = call.get_call_metadata();
+		if Self::is_own_pallet(metadata.pallet_name.as_bytes()) {
+			return true;
+		}
+		if Self::maintenance_mode() {
+			return false;
+		}
+
+		let pallet = metadata.pallet_name.as_bytes();
+		!<BlockedPallets<T>>::contains_key(pallet) &&
+			!<BlockedCalls<T>>::contains_key(pallet, metadata.function_name.as_bytes())
+	}
+}
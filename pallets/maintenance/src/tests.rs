@@ -0,0 +1,116 @@
+use crate::{Error, Event as MaintenanceEvent, mock::*};
+use frame_support::{assert_noop, assert_ok, dispatch::Dispatchable, traits::Filter};
+use sp_runtime::DispatchError::BadOrigin;
+
+fn last_event() -> Event {
+	System::events().pop().expect("an event was deposited").event
+}
+
+fn remark() -> Call {
+	Call::System(frame_system::Call::remark(vec![]))
+}
+
+fn set_heap_pages() -> Call {
+	Call::System(frame_system::Call::set_heap_pages(64))
+}
+
+fn set_maintenance_mode(enabled: bool) -> Call {
+	Call::Maintenance(crate::Call::set_maintenance_mode(enabled))
+}
+
+#[test]
+fn everything_is_allowed_by_default() {
+	new_test_ext().execute_with(|| {
+		assert!(Maintenance::filter(&remark()));
+		assert!(Maintenance::filter(&set_heap_pages()));
+		assert_ok!(remark().dispatch(Origin::signed(1)));
+	});
+}
+
+#[test]
+fn maintenance_mode_allows_only_safe_calls() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Maintenance::set_maintenance_mode(Origin::root(), true));
+		assert_eq!(last_event(), Event::pallet_maintenance(MaintenanceEvent::MaintenanceModeSet(true)));
+
+		assert!(!Maintenance::filter(&remark()));
+		assert_noop!(remark().dispatch(Origin::signed(1)), BadOrigin);
+		assert!(Maintenance::filter(&set_heap_pages()));
+		// The pallet's own calls stay available to lift maintenance mode.
+		assert!(Maintenance::filter(&set_maintenance_mode(false)));
+
+		assert_ok!(Maintenance::set_maintenance_mode(Origin::root(), false));
+		assert!(Maintenance::filter(&remark()));
+	});
+}
+
+#[test]
+fn maintenance_mode_must_change() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Maintenance::set_maintenance_mode(Origin::root(), false), Error::<Test>::AlreadySet);
+		assert_ok!(Maintenance::set_maintenance_mode(Origin::root(), true));
+		assert_noop!(Maintenance::set_maintenance_mode(Origin::root(), true), Error::<Test>::AlreadySet);
+	});
+}
+
+#[test]
+fn single_calls_can_be_blocked() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Maintenance::block(Origin::root(), b"System".to_vec(), Some(b"remark".to_vec())));
+		assert_eq!(
+			last_event(),
+			Event::pallet_maintenance(MaintenanceEvent::Blocked(b"System".to_vec(), Some(b"remark".to_vec()))),
+		);
+		assert!(!Maintenance::filter(&remark()));
+		assert!(Maintenance::filter(&Call::System(frame_system::Call::set_storage(vec![]))));
+
+		assert_ok!(Maintenance::unblock(Origin::root(), b"System".to_vec(), Some(b"remark".to_vec())));
+		assert!(Maintenance::filter(&remark()));
+	});
+}
+
+#[test]
+fn whole_pallets_can_be_blocked_except_safe_calls() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Maintenance::block(Origin::root(), b"System".to_vec(), None));
+		assert!(!Maintenance::filter(&remark()));
+		assert!(Maintenance::filter(&set_heap_pages()));
+
+		assert_ok!(Maintenance::unblock(Origin::root(), b"System".to_vec(), None));
+		assert!(Maintenance::filter(&remark()));
+	});
+}
+
+#[test]
+fn invalid_blocklist_changes_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Maintenance::block(Origin::root(), b"Maintenance".to_vec(), None),
+			Error::<Test>::CannotBlockSelf,
+		);
+		assert_noop!(
+			Maintenance::unblock(Origin::root(), b"System".to_vec(), None),
+			Error::<Test>::NotBlocked,
+		);
+
+		assert_ok!(Maintenance::block(Origin::root(), b"System".to_vec(), None));
+		assert_noop!(
+			Maintenance::block(Origin::root(), b"System".to_vec(), None),
+			Error::<Test>::AlreadyBlocked,
+		);
+		// Blocking the pallet and blocking one of its calls are separate entries.
+		assert_noop!(
+			Maintenance::unblock(Origin::root(), b"System".to_vec(), Some(b"remark".to_vec())),
+			Error::<Test>::NotBlocked,
+		);
+	});
+}
+
+#[test]
+fn changes_require_the_maintenance_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Maintenance::set_maintenance_mode(Origin::signed(1), true), BadOrigin);
+		assert_noop!(Maintenance::block(Origin::signed(1), b"System".to_vec(), None), BadOrigin);
+		assert_noop!(Maintenance::unblock(Origin::signed(1), b"System".to_vec(), None), BadOrigin);
+	});
+}
@@ -0,0 +1,68 @@
+//! Weights for pallet_maintenance
+//!
+//! These values are estimates, not benchmark results. Each call is charged one
+//! `ExtrinsicBaseWeight` for its execution, plus the database reads and writes it makes, priced
+//! by `DbWeight`. Replace them with the output of
+//!
+//! ```text
+//! ./target/release/node-template benchmark --chain=dev --steps=50 --repeat=20 \
+//!     --pallet=pallet_maintenance --extrinsic=* --execution=wasm --wasm-execution=compiled \
+//!     --heap-pages=4096 --output=./pallets/maintenance/src/weights.rs
+//! ```
+//!
+//! on reference hardware before relying on them.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{Weight, constants::{ExtrinsicBaseWeight, RocksDbWeight}},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_maintenance.
+pub trait WeightInfo {
+	fn set_maintenance_mode() -> Weight;
+	fn block() -> Weight;
+	fn unblock() -> Weight;
+}
+
+/// Weights for pallet_maintenance using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn set_maintenance_mode() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn block() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn unblock() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn set_maintenance_mode() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn block() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn unblock() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+}
@@ -0,0 +1,94 @@
+//! RPC interface for the names pallet.
+
+use std::sync::Arc;
+use codec::Codec;
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+pub use pallet_names_rpc_runtime_api::NamesApi as NamesRuntimeApi;
+
+#[rpc]
+pub trait NamesApi<BlockHash, AccountId> {
+	/// Returns the account registered under `name` at the given block (or the best block).
+	#[rpc(name = "names_accountOf")]
+	fn account_of(&self, name: String, at: Option<BlockHash>) -> Result<Option<AccountId>>;
+
+	/// Returns the name registered by `who` at the given block (or the best block).
+	#[rpc(name = "names_nameOf")]
+	fn name_of(&self, who: AccountId, at: Option<BlockHash>) -> Result<Option<String>>;
+}
+
+/// A struct that implements the [`NamesApi`].
+pub struct Names<C, P> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> Names<C, P> {
+	/// Create new `Names` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query names.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId> NamesApi<<Block as BlockT>::Hash, AccountId> for Names<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: NamesRuntimeApi<Block, AccountId>,
+	AccountId: Codec,
+{
+	fn account_of(
+		&self,
+		name: String,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<AccountId>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.account_of(&at, name.into_bytes()).map_err(runtime_error)
+	}
+
+	fn name_of(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<String>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		let name = api.name_of(&at, who).map_err(runtime_error)?;
+		// Names are made of ASCII characters, see `pallet_names`.
+		Ok(name.map(|name| String::from_utf8_lossy(&name).into_owned()))
+	}
+}
@@ -0,0 +1,17 @@
+//! Runtime API definition for the names pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	pub trait NamesApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// The account registered under `name`, if any.
+		fn account_of(name: Vec<u8>) -> Option<AccountId>;
+		/// The name registered by `who`, if any.
+		fn name_of(who: AccountId) -> Option<Vec<u8>>;
+	}
+}
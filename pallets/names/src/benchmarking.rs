@@ -0,0 +1,70 @@
+//! Benchmarking setup for pallet-names
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite, whitelisted_caller};
+use frame_support::traits::{Currency, EnsureOrigin, Get};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Bounded, StaticLookup};
+use sp_std::prelude::*;
+#[allow(unused)]
+use crate::Pallet as Names;
+
+/// A valid name of `len` bytes.
+fn name(len: u32) -> Vec<u8> {
+	vec![b'a'; len as usize]
+}
+
+/// `who` with enough funds and a name of maximum length, so that replacing it frees a key.
+fn named<T: Config>(who: &T::AccountId) {
+	T::Currency::make_free_balance_be(who, BalanceOf::<T>::max_value());
+	Names::<T>::set_name(RawOrigin::Signed(who.clone()).into(), vec![b'z'; T::MaxLength::get() as usize])
+		.expect("the name is valid and free");
+}
+
+benchmarks! {
+	// Replacing a name touches both maps twice.
+	set_name {
+		let n in (T::MinLength::get()) .. (T::MaxLength::get());
+		let caller: T::AccountId = whitelisted_caller();
+		named::<T>(&caller);
+	}: _(RawOrigin::Signed(caller.clone()), name(n))
+	verify {
+		assert_eq!(Names::<T>::name_of(&caller), Some(name(n)));
+	}
+
+	clear_name {
+		let caller: T::AccountId = whitelisted_caller();
+		named::<T>(&caller);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Names::<T>::name_of(&caller), None);
+	}
+
+	force_name {
+		let n in (T::MinLength::get()) .. (T::MaxLength::get());
+		let origin = T::ForceOrigin::successful_origin();
+		let target: T::AccountId = account("target", 0, 0);
+		named::<T>(&target);
+		let lookup = T::Lookup::unlookup(target.clone());
+	}: _(origin, lookup, name(n))
+	verify {
+		assert_eq!(Names::<T>::name_of(&target), Some(name(n)));
+	}
+
+	kill_name {
+		let origin = T::ForceOrigin::successful_origin();
+		let target: T::AccountId = account("target", 0, 0);
+		named::<T>(&target);
+		let lookup = T::Lookup::unlookup(target.clone());
+	}: _(origin, lookup)
+	verify {
+		assert_eq!(Names::<T>::name_of(&target), None);
+	}
+}
+
+impl_benchmark_test_suite!(
+	Names,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
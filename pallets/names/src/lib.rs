@@ -0,0 +1,254 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Names Pallet
+//!
+//! Lets accounts register a unique name, so that wallets and explorers can show and resolve names
+//! instead of SS58 addresses. Setting a name reserves `ReservationFee`, which is returned when the
+//! name is cleared and slashed when `ForceOrigin` kills it.
+//!
+//! Names are made of lowercase ASCII letters, digits, `-` and `_`, so that two different names
+//! cannot look alike. The genesis config names accounts for free, e.g. the validators and the sudo
+//! key.
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{
+		dispatch::{DispatchResult, DispatchResultWithPostInfo}, pallet_prelude::*,
+		traits::{Currency, OnUnbalanced, ReservableCurrency},
+	};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::{StaticLookup, Zero};
+	use sp_std::prelude::*;
+	use super::WeightInfo;
+
+	pub(crate) type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::NegativeImbalance;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency the name deposit is reserved in.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The deposit reserved for a name.
+		#[pallet::constant]
+		type ReservationFee: Get<BalanceOf<Self>>;
+		/// What to do with the deposits of killed names.
+		type Slashed: OnUnbalanced<NegativeImbalanceOf<Self>>;
+		/// The origin allowed to set and kill the names of other accounts.
+		type ForceOrigin: EnsureOrigin<Self::Origin>;
+		/// The shortest allowed name.
+		#[pallet::constant]
+		type MinLength: Get<u32>;
+		/// The longest allowed name.
+		#[pallet::constant]
+		type MaxLength: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The name of an account and the deposit reserved for it.
+	#[pallet::storage]
+	pub type NameOf<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, (Vec<u8>, BalanceOf<T>)>;
+
+	/// The account registered under a name.
+	#[pallet::storage]
+	#[pallet::getter(fn account_of)]
+	pub type AccountOf<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8>, T::AccountId>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The names of accounts at genesis, without deposit.
+		pub names: Vec<(T::AccountId, Vec<u8>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { names: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (who, name) in &self.names {
+				Pallet::<T>::ensure_valid(name).expect("genesis names must be valid; qed");
+				assert!(!<AccountOf<T>>::contains_key(name), "genesis names must be unique");
+				assert!(!<NameOf<T>>::contains_key(who), "genesis accounts must have one name");
+				Pallet::<T>::insert_name(who, name.clone(), Zero::zero());
+			}
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId", BalanceOf<T> = "Balance")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An account set its name. [who, name]
+		NameSet(T::AccountId, Vec<u8>),
+		/// An account cleared its name and got its deposit back. [who, deposit]
+		NameCleared(T::AccountId, BalanceOf<T>),
+		/// The name of an account was set by `ForceOrigin`. [who, name]
+		NameForced(T::AccountId, Vec<u8>),
+		/// The name of an account was removed by `ForceOrigin` and its deposit slashed.
+		/// [who, deposit]
+		NameKilled(T::AccountId, BalanceOf<T>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The name is shorter than `MinLength`.
+		TooShort,
+		/// The name is longer than `MaxLength`.
+		TooLong,
+		/// The name has a character other than a lowercase ASCII letter, a digit, `-` or `_`.
+		InvalidCharacter,
+		/// Another account has this name.
+		NameTaken,
+		/// The account has no name.
+		Unnamed,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the name of the signer, replacing its current one.
+		///
+		/// The first name of an account reserves `ReservationFee`.
+		#[pallet::weight(T::WeightInfo::set_name(name.len() as u32))]
+		pub fn set_name(origin: OriginFor<T>, name: Vec<u8>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			Self::ensure_available(&who, &name)?;
+
+			let deposit = match <NameOf<T>>::get(&who) {
+				Some((_, deposit)) => deposit,
+				None => {
+					let deposit = T::ReservationFee::get();
+					T::Currency::reserve(&who, deposit)?;
+					deposit
+				},
+			};
+			Self::insert_name(&who, name.clone(), deposit);
+			Self::deposit_event(Event::NameSet(who, name));
+			Ok(().into())
+		}
+
+		/// Clear the name of the signer and return its deposit.
+		#[pallet::weight(T::WeightInfo::clear_name())]
+		pub fn clear_name(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+			let deposit = Self::remove_name(&who)?;
+
+			let _ = T::Currency::unreserve(&who, deposit);
+			Self::deposit_event(Event::NameCleared(who, deposit));
+			Ok(().into())
+		}
+
+		/// Set the name of `target`, keeping the deposit it already has, if any.
+		///
+		/// The dispatch origin must be `ForceOrigin`.
+		#[pallet::weight(T::WeightInfo::force_name(name.len() as u32))]
+		pub fn force_name(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+			name: Vec<u8>,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			Self::ensure_available(&target, &name)?;
+
+			let deposit = <NameOf<T>>::get(&target).map_or_else(Zero::zero, |(_, deposit)| deposit);
+			Self::insert_name(&target, name.clone(), deposit);
+			Self::deposit_event(Event::NameForced(target, name));
+			Ok(().into())
+		}
+
+		/// Remove the name of `target` and slash its deposit.
+		///
+		/// The dispatch origin must be `ForceOrigin`.
+		#[pallet::weight(T::WeightInfo::kill_name())]
+		pub fn kill_name(
+			origin: OriginFor<T>,
+			target: <T::Lookup as StaticLookup>::Source,
+		) -> DispatchResultWithPostInfo {
+			T::ForceOrigin::ensure_origin(origin)?;
+			let target = T::Lookup::lookup(target)?;
+			let deposit = Self::remove_name(&target)?;
+
+			T::Slashed::on_unbalanced(T::Currency::slash_reserved(&target, deposit).0);
+			Self::deposit_event(Event::NameKilled(target, deposit));
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The name of `who`, if any.
+		pub fn name_of(who: &T::AccountId) -> Option<Vec<u8>> {
+			<NameOf<T>>::get(who).map(|(name, _)| name)
+		}
+
+		/// Ensure `name` is well-formed.
+		pub(crate) fn ensure_valid(name: &[u8]) -> DispatchResult {
+			ensure!(name.len() >= T::MinLength::get() as usize, Error::<T>::TooShort);
+			ensure!(name.len() <= T::MaxLength::get() as usize, Error::<T>::TooLong);
+			ensure!(
+				name.iter().all(|c| matches!(c, b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_')),
+				Error::<T>::InvalidCharacter,
+			);
+			Ok(())
+		}
+
+		/// Ensure `name` is well-formed and not the name of an account other than `who`.
+		fn ensure_available(who: &T::AccountId, name: &[u8]) -> DispatchResult {
+			Self::ensure_valid(name)?;
+			ensure!(
+				Self::account_of(name).map_or(true, |owner| &owner == who),
+				Error::<T>::NameTaken,
+			);
+			Ok(())
+		}
+
+		/// Name `who`, freeing its previous name.
+		pub(crate) fn insert_name(who: &T::AccountId, name: Vec<u8>, deposit: BalanceOf<T>) {
+			<AccountOf<T>>::insert(&name, who);
+			if let Some((old, _)) = <NameOf<T>>::get(who) {
+				if old != name {
+					<AccountOf<T>>::remove(&old);
+				}
+			}
+			<NameOf<T>>::insert(who, (name, deposit));
+		}
+
+		/// Remove the name of `who`, returning its deposit.
+		fn remove_name(who: &T::AccountId) -> Result<BalanceOf<T>, DispatchError> {
+			let (name, deposit) = <NameOf<T>>::take(who).ok_or(Error::<T>::Unnamed)?;
+			<AccountOf<T>>::remove(&name);
+			Ok(deposit)
+		}
+	}
+}
@@ -0,0 +1,90 @@
+use crate::{Error, Event as NamesEvent, mock::*};
+use frame_support::{assert_noop, assert_ok, traits::ReservableCurrency};
+use sp_runtime::DispatchError::BadOrigin;
+
+fn last_event() -> Event {
+	System::events().pop().expect("an event was deposited").event
+}
+
+#[test]
+fn genesis_names_accounts() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(Names::name_of(&VALIDATOR), Some(b"validator".to_vec()));
+		assert_eq!(Names::account_of(b"validator".to_vec()), Some(VALIDATOR));
+	});
+}
+
+#[test]
+fn names_reserve_a_deposit_and_resolve_both_ways() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Names::set_name(Origin::signed(1), b"alice".to_vec()));
+		assert_eq!(last_event(), Event::pallet_names(NamesEvent::NameSet(1, b"alice".to_vec())));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_eq!(Names::name_of(&1), Some(b"alice".to_vec()));
+		assert_eq!(Names::account_of(b"alice".to_vec()), Some(1));
+
+		// Renaming frees the old name and keeps the deposit.
+		assert_ok!(Names::set_name(Origin::signed(1), b"alice-2".to_vec()));
+		assert_eq!(Balances::reserved_balance(1), 10);
+		assert_eq!(Names::account_of(b"alice".to_vec()), None);
+		assert_eq!(Names::account_of(b"alice-2".to_vec()), Some(1));
+
+		assert_ok!(Names::clear_name(Origin::signed(1)));
+		assert_eq!(last_event(), Event::pallet_names(NamesEvent::NameCleared(1, 10)));
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Names::name_of(&1), None);
+		assert_eq!(Names::account_of(b"alice-2".to_vec()), None);
+		assert_noop!(Names::clear_name(Origin::signed(1)), Error::<Test>::Unnamed);
+	});
+}
+
+#[test]
+fn invalid_and_taken_names_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Names::set_name(Origin::signed(1), b"al".to_vec()), Error::<Test>::TooShort);
+		assert_noop!(Names::set_name(Origin::signed(1), vec![b'a'; 17]), Error::<Test>::TooLong);
+		assert_noop!(Names::set_name(Origin::signed(1), b"Alice".to_vec()), Error::<Test>::InvalidCharacter);
+		assert_noop!(Names::set_name(Origin::signed(1), b"al ice".to_vec()), Error::<Test>::InvalidCharacter);
+		assert_noop!(Names::set_name(Origin::signed(1), b"validator".to_vec()), Error::<Test>::NameTaken);
+
+		// Setting the same name again is fine.
+		assert_ok!(Names::set_name(Origin::signed(1), b"alice".to_vec()));
+		assert_ok!(Names::set_name(Origin::signed(1), b"alice".to_vec()));
+		assert_eq!(Names::account_of(b"alice".to_vec()), Some(1));
+	});
+}
+
+#[test]
+fn names_need_the_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(Balances::reserve(&1, 95));
+		assert!(Names::set_name(Origin::signed(1), b"alice".to_vec()).is_err());
+		assert_eq!(Names::name_of(&1), None);
+	});
+}
+
+#[test]
+fn force_origin_can_set_and_kill_names() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(Names::force_name(Origin::signed(1), 2, b"bob".to_vec()), BadOrigin);
+		assert_noop!(Names::kill_name(Origin::signed(1), 2), BadOrigin);
+
+		assert_ok!(Names::set_name(Origin::signed(2), b"bob".to_vec()));
+		assert_ok!(Names::force_name(Origin::root(), 2, b"robert".to_vec()));
+		assert_eq!(last_event(), Event::pallet_names(NamesEvent::NameForced(2, b"robert".to_vec())));
+		assert_eq!(Names::account_of(b"bob".to_vec()), None);
+		assert_eq!(Names::account_of(b"robert".to_vec()), Some(2));
+
+		assert_ok!(Names::kill_name(Origin::root(), 2));
+		assert_eq!(last_event(), Event::pallet_names(NamesEvent::NameKilled(2, 10)));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(2), 90);
+		assert_eq!(Names::name_of(&2), None);
+		assert_noop!(Names::kill_name(Origin::root(), 2), Error::<Test>::Unnamed);
+
+		// Names set by `ForceOrigin` on unnamed accounts carry no deposit.
+		assert_ok!(Names::force_name(Origin::root(), 5, b"charlie".to_vec()));
+		assert_ok!(Names::kill_name(Origin::root(), 5));
+		assert_eq!(last_event(), Event::pallet_names(NamesEvent::NameKilled(5, 0)));
+	});
+}
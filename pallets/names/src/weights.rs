@@ -0,0 +1,79 @@
+//! Weights for pallet_names
+//!
+//! These values are estimates, not benchmark results. Each call is charged one
+//! `ExtrinsicBaseWeight` for its execution, plus the database reads and writes it makes, priced
+//! by `DbWeight`, regardless of the length of the name. Replace them with the output of
+//!
+//! ```text
+//! ./target/release/node-template benchmark --chain=dev --steps=50 --repeat=20 \
+//!     --pallet=pallet_names --extrinsic=* --execution=wasm --wasm-execution=compiled \
+//!     --heap-pages=4096 --output=./pallets/names/src/weights.rs
+//! ```
+//!
+//! on reference hardware before relying on them.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{Weight, constants::{ExtrinsicBaseWeight, RocksDbWeight}},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_names.
+pub trait WeightInfo {
+	fn set_name(n: u32, ) -> Weight;
+	fn clear_name() -> Weight;
+	fn force_name(n: u32, ) -> Weight;
+	fn kill_name() -> Weight;
+}
+
+/// Weights for pallet_names using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn set_name(_n: u32, ) -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn clear_name() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn force_name(_n: u32, ) -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn kill_name() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn set_name(_n: u32, ) -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn clear_name() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn force_name(_n: u32, ) -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn kill_name() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+}
@@ -0,0 +1,113 @@
+//! Off-chain worker of the randomness beacon.
+//!
+//! On every block, for each local Aura key of a current authority, the worker commits to a fresh
+//! seed if the authority has no pending commitment. Once the round of the commitment has ended,
+//! it reveals the seed in the first block of the next round it can. Seeds are drawn from the
+//! node's own randomness and kept in the persistent off-chain storage under [`SEED_KEY_PREFIX`]
+//! followed by the encoded key, until they are revealed.
+//!
+//! A seed lost with the node's off-chain database cannot be revealed, and its authority is
+//! reported once the round after its commitment ends. Authorities should not move their Aura
+//! keys to another node while a commitment is pending.
+
+use codec::{Encode, Decode};
+use frame_support::debug;
+use frame_system::offchain::SubmitTransaction;
+use sp_runtime::{
+	offchain::storage::StorageValueRef,
+	traits::{Hash, One, Saturating},
+	RuntimeAppPublic,
+};
+use sp_std::prelude::*;
+
+use crate::{Call, CommitPayload, Config, Pallet};
+
+/// Prefix of the persistent off-chain storage keys holding the seeds yet to be revealed.
+pub const SEED_KEY_PREFIX: &[u8] = b"randomness-beacon::seed::";
+
+/// A seed committed to by the off-chain worker.
+#[derive(Encode, Decode)]
+struct PendingSeed<Hash, BlockNumber> {
+	seed: Hash,
+	/// The `block_number` of the commitment.
+	block_number: BlockNumber,
+}
+
+impl<T: Config> Pallet<T> {
+	pub(crate) fn run_offchain_worker(block_number: T::BlockNumber) {
+		let authorities = <pallet_aura::Module<T>>::authorities();
+		for authority in T::AuthorityId::all() {
+			if !authorities.contains(&authority) {
+				continue;
+			}
+			if let Err(e) = Self::commit_or_reveal(&authority, block_number) {
+				debug::error!("Randomness beacon off-chain worker error: {}", e);
+			}
+		}
+	}
+
+	fn commit_or_reveal(authority: &T::AuthorityId, block_number: T::BlockNumber) -> Result<(), &'static str> {
+		let mut key = SEED_KEY_PREFIX.to_vec();
+		authority.encode_to(&mut key);
+		let stored = StorageValueRef::persistent(&key);
+		let pending = stored.get::<PendingSeed<T::Hash, T::BlockNumber>>().flatten();
+
+		if let Some((commitment, committed_at)) = Self::commitment(authority) {
+			// The transaction goes into the next block at the earliest.
+			let next = block_number.saturating_add(One::one());
+			if Self::round_of(next) <= Self::round_of(committed_at) {
+				return Ok(());
+			}
+			let seed = pending
+				.filter(|pending| T::Hashing::hash_of(&pending.seed) == commitment)
+				.ok_or("the seed of the pending commitment is lost")?
+				.seed;
+			let call = Call::reveal_unsigned(authority.clone(), seed);
+			return SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
+				.map_err(|()| "unable to submit the reveal");
+		}
+
+		if pending.map_or(false, |pending| Self::in_flight(authority, &pending, block_number)) {
+			return Ok(());
+		}
+
+		let seed = T::Hashing::hash(&sp_io::offchain::random_seed());
+		let payload = CommitPayload {
+			authority: authority.clone(),
+			commitment: T::Hashing::hash_of(&seed),
+			block_number,
+		};
+		let signature = payload.using_encoded(|encoded| authority.sign(&encoded))
+			.ok_or("unable to sign the commitment")?;
+
+		// The seed is stored before the commitment is sent, so it can always be revealed.
+		// `mutate` is atomic, so of the workers of consecutive blocks running concurrently, only
+		// one stores a seed and commits to it. The others would overwrite a seed whose commitment
+		// is already on its way, and the pool keeps only one commitment per authority.
+		let res = stored.mutate(|pending: Option<Option<PendingSeed<T::Hash, T::BlockNumber>>>| {
+			match pending.flatten() {
+				Some(pending) if Self::in_flight(authority, &pending, block_number) => Err(()),
+				_ => Ok(PendingSeed { seed, block_number }),
+			}
+		});
+		if !matches!(res, Ok(Ok(_))) {
+			return Ok(());
+		}
+
+		let call = Call::commit_unsigned(payload, signature);
+		SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(call.into())
+			.map_err(|()| "unable to submit the commitment")
+	}
+
+	/// Whether the commitment to `pending` may still be in the transaction pool at `block_number`,
+	/// not yet recorded on-chain but not expired either.
+	fn in_flight(
+		authority: &T::AuthorityId,
+		pending: &PendingSeed<T::Hash, T::BlockNumber>,
+		block_number: T::BlockNumber,
+	) -> bool {
+		let recorded = Self::last_committed(authority).map_or(false, |last| last >= pending.block_number);
+		let expired = block_number >= pending.block_number.saturating_add(T::RoundLength::get());
+		!recorded && !expired
+	}
+}
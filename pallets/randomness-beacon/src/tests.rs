@@ -0,0 +1,287 @@
+use std::sync::Arc;
+use crate::{CommitPayload, Error, Event as RandomnessBeaconEvent, RevealMissedOffence, mock::*};
+use codec::{Encode, Decode};
+use frame_support::{assert_noop, assert_ok, traits::{Hooks, Randomness}};
+use parking_lot::RwLock;
+use sp_core::{
+	H256,
+	offchain::{testing::{self, PoolState}, OffchainExt, StorageKind, TransactionPoolExt},
+};
+use sp_runtime::{
+	testing::{TestSignature, UintAuthorityId},
+	traits::{BlakeTwo256, Hash, ValidateUnsigned},
+	transaction_validity::{InvalidTransaction, TransactionSource},
+	Perbill,
+};
+use sp_staking::offence::Offence;
+
+fn last_event() -> Event {
+	System::events().pop().expect("an event was deposited").event
+}
+
+fn seed(n: u8) -> H256 {
+	H256::repeat_byte(n)
+}
+
+/// A commitment by `authority` to `seed`, signed by `signer`.
+fn signed_commitment(
+	authority: u64,
+	seed: H256,
+	block_number: u64,
+	signer: u64,
+) -> (CommitPayload<UintAuthorityId, H256, u64>, TestSignature) {
+	let payload = CommitPayload {
+		authority: UintAuthorityId(authority),
+		commitment: BlakeTwo256::hash_of(&seed),
+		block_number,
+	};
+	let signature = TestSignature(signer, payload.encode());
+	(payload, signature)
+}
+
+fn commit(authority: u64, seed: H256, block_number: u64) -> frame_support::dispatch::DispatchResultWithPostInfo {
+	let (payload, signature) = signed_commitment(authority, seed, block_number, authority);
+	RandomnessBeacon::commit_unsigned(Origin::none(), payload, signature)
+}
+
+fn reveal(authority: u64, seed: H256) -> frame_support::dispatch::DispatchResultWithPostInfo {
+	RandomnessBeacon::reveal_unsigned(Origin::none(), UintAuthorityId(authority), seed)
+}
+
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		RandomnessBeacon::on_initialize(System::block_number());
+	}
+}
+
+#[test]
+fn fallback_is_used_until_a_round_closes() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(RandomnessBeacon::random(b"subject"), BlakeTwo256::hash(b"subject"));
+
+		assert_ok!(commit(1, seed(1), 1));
+		run_to_block(10);
+		assert_ok!(reveal(1, seed(1)));
+		assert_eq!(RandomnessBeacon::random(b"subject"), BlakeTwo256::hash(b"subject"));
+
+		run_to_block(20);
+		assert_ne!(RandomnessBeacon::random(b"subject"), BlakeTwo256::hash(b"subject"));
+	});
+}
+
+#[test]
+fn reveals_are_mixed_in_when_the_round_closes() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(commit(1, seed(1), 1));
+		assert_eq!(last_event(), Event::pallet_randomness_beacon(RandomnessBeaconEvent::Committed(UintAuthorityId(1))));
+		assert_noop!(reveal(1, seed(1)), Error::<Test>::TooEarly);
+
+		run_to_block(10);
+		assert_ok!(reveal(1, seed(1)));
+		assert_eq!(last_event(), Event::pallet_randomness_beacon(RandomnessBeaconEvent::Revealed(UintAuthorityId(1))));
+		assert_eq!(RandomnessBeacon::commitment(UintAuthorityId(1)), None);
+		assert_eq!(RandomnessBeacon::closed_at(), None);
+
+		run_to_block(20);
+		assert_eq!(RandomnessBeacon::closed_at(), Some(20));
+		let first = RandomnessBeacon::random(b"subject");
+		assert_ne!(first, BlakeTwo256::hash(b"subject"));
+		assert_ne!(first, RandomnessBeacon::random(b"other subject"));
+
+		assert_ok!(commit(2, seed(2), 20));
+		run_to_block(30);
+		assert_ok!(reveal(2, seed(2)));
+		assert_eq!(RandomnessBeacon::random(b"subject"), first);
+
+		run_to_block(40);
+		assert_ne!(RandomnessBeacon::random(b"subject"), first);
+	});
+}
+
+#[test]
+fn the_order_of_reveals_does_not_matter() {
+	let seed_after = |reveals: [(u64, u64); 2]| new_test_ext().execute_with(|| {
+		assert_ok!(commit(1, seed(1), 1));
+		assert_ok!(commit(2, seed(2), 1));
+		for (authority, block) in reveals.iter() {
+			run_to_block(*block);
+			assert_ok!(reveal(*authority, seed(*authority as u8)));
+		}
+		run_to_block(20);
+		RandomnessBeacon::seed()
+	});
+
+	assert_eq!(seed_after([(1, 10), (2, 10)]), seed_after([(2, 11), (1, 19)]));
+}
+
+#[test]
+fn reveals_must_match_a_commitment() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(reveal(1, seed(1)), Error::<Test>::NoCommitment);
+
+		assert_ok!(commit(1, seed(1), 1));
+		run_to_block(10);
+		assert_noop!(reveal(1, seed(2)), Error::<Test>::InvalidSeed);
+		assert_noop!(reveal(2, seed(1)), Error::<Test>::NoCommitment);
+	});
+}
+
+#[test]
+fn only_authorities_commit_once_at_a_time() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(commit(4, seed(4), 1), Error::<Test>::NotAuthority);
+
+		assert_ok!(commit(1, seed(1), 1));
+		assert_noop!(commit(1, seed(2), 2), Error::<Test>::AlreadyCommitted);
+
+		run_to_block(10);
+		assert_ok!(reveal(1, seed(1)));
+		// Replaying the revealed commitment is refused.
+		assert_noop!(commit(1, seed(1), 1), Error::<Test>::StaleCommitment);
+		assert_ok!(commit(1, seed(2), 2));
+	});
+}
+
+#[test]
+fn commits_must_be_signed_by_the_authority() {
+	new_test_ext().execute_with(|| {
+		let validate = |authority, block_number, signer| {
+			let (payload, signature) = signed_commitment(authority, seed(1), block_number, signer);
+			let call = crate::Call::commit_unsigned(payload, signature);
+			RandomnessBeacon::validate_unsigned(TransactionSource::External, &call)
+		};
+
+		assert!(validate(1, 1, 1).is_ok());
+		assert_eq!(validate(1, 1, 2), InvalidTransaction::BadProof.into());
+		assert_eq!(validate(4, 1, 4), InvalidTransaction::BadProof.into());
+		assert_eq!(validate(1, 2, 1), InvalidTransaction::Future.into());
+
+		assert_ok!(commit(1, seed(1), 1));
+		assert_eq!(validate(1, 1, 1), InvalidTransaction::Stale.into());
+	});
+}
+
+#[test]
+fn reveals_are_validated_against_the_commitment() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(commit(1, seed(1), 1));
+
+		let validate = |seed| {
+			let call = crate::Call::reveal_unsigned(UintAuthorityId(1), seed);
+			RandomnessBeacon::validate_unsigned(TransactionSource::External, &call)
+		};
+		assert_eq!(validate(seed(1)), InvalidTransaction::Future.into());
+
+		run_to_block(10);
+		assert!(validate(seed(1)).is_ok());
+		assert_eq!(validate(seed(2)), InvalidTransaction::BadProof.into());
+	});
+}
+
+#[test]
+fn missed_reveals_are_reported() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(commit(2, seed(2), 1));
+
+		// The seed is due in the round after the commitment.
+		run_to_block(2 * RoundLength::get() - 1);
+		assert!(RandomnessBeacon::commitment(UintAuthorityId(2)).is_some());
+
+		run_to_block(2 * RoundLength::get());
+		assert_eq!(RandomnessBeacon::commitment(UintAuthorityId(2)), None);
+		assert_eq!(
+			offences(),
+			vec![RevealMissedOffence { committed_at: 1, session_index: SESSION, validator_set_count: 3, offender: 2 }],
+		);
+		assert_eq!(last_event(), Event::pallet_randomness_beacon(RandomnessBeaconEvent::RevealMissed(UintAuthorityId(2))));
+	});
+}
+
+#[test]
+fn authorities_that_left_are_not_penalized() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(commit(3, seed(3), 1));
+		// Aura only changes its authorities on new sessions, which the mock has none of.
+		let key = [sp_io::hashing::twox_128(b"Aura"), sp_io::hashing::twox_128(b"Authorities")].concat();
+		frame_support::storage::unhashed::put(&key, &vec![UintAuthorityId(1), UintAuthorityId(2)]);
+
+		run_to_block(2 * RoundLength::get());
+		assert_eq!(RandomnessBeacon::commitment(UintAuthorityId(3)), None);
+		assert!(offences().is_empty());
+	});
+}
+
+#[test]
+fn few_missed_reveals_are_not_slashed() {
+	type MissedOffence = RevealMissedOffence<u64, u64>;
+	assert_eq!(MissedOffence::slash_fraction(1, 3), Perbill::zero());
+	assert_eq!(MissedOffence::slash_fraction(11, 100), Perbill::zero());
+	assert_eq!(MissedOffence::slash_fraction(6, 10), Perbill::from_percent(7));
+}
+
+fn offchain_test_ext() -> (sp_io::TestExternalities, Arc<RwLock<PoolState>>) {
+	let (offchain, _) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	(t, pool_state)
+}
+
+fn submitted(pool_state: &RwLock<PoolState>) -> Vec<crate::Call<Test>> {
+	pool_state.write().transactions.drain(..).map(|tx| {
+		match Extrinsic::decode(&mut &*tx).unwrap().call {
+			Call::RandomnessBeacon(call) => call,
+			call => panic!("unexpected call {:?}", call),
+		}
+	}).collect()
+}
+
+#[test]
+fn offchain_worker_commits_then_reveals() {
+	let (mut t, pool_state) = offchain_test_ext();
+	// Authority 1 is local, 4 is not an authority.
+	UintAuthorityId::set_all_keys(vec![1, 4]);
+
+	t.execute_with(|| {
+		RandomnessBeacon::offchain_worker(1);
+		let (payload, signature) = match submitted(&pool_state).as_slice() {
+			[crate::Call::commit_unsigned(payload, signature)] => (payload.clone(), signature.clone()),
+			calls => panic!("expected a single commit, got {:?}", calls),
+		};
+		assert_eq!(payload.authority, UintAuthorityId(1));
+		assert_eq!(payload.block_number, 1);
+
+		// The commitment is still in the pool, so its seed is kept rather than replaced.
+		let stored_seed = || {
+			let mut key = crate::SEED_KEY_PREFIX.to_vec();
+			UintAuthorityId(1).encode_to(&mut key);
+			sp_io::offchain::local_storage_get(StorageKind::PERSISTENT, &key)
+		};
+		let committed_seed = stored_seed();
+		assert!(committed_seed.is_some());
+		RandomnessBeacon::offchain_worker(2);
+		assert!(submitted(&pool_state).is_empty());
+		assert_eq!(stored_seed(), committed_seed);
+
+		System::set_block_number(2);
+		assert_ok!(RandomnessBeacon::commit_unsigned(Origin::none(), payload, signature));
+		// Nothing is revealed before the round of the commitment ends.
+		RandomnessBeacon::offchain_worker(2);
+		assert!(submitted(&pool_state).is_empty());
+
+		// The reveal goes into the first block of the next round.
+		RandomnessBeacon::offchain_worker(9);
+		let seed = match submitted(&pool_state).as_slice() {
+			[crate::Call::reveal_unsigned(authority, seed)] if authority == &UintAuthorityId(1) => *seed,
+			calls => panic!("expected a single reveal, got {:?}", calls),
+		};
+
+		run_to_block(10);
+		assert_ok!(reveal(1, seed));
+		RandomnessBeacon::offchain_worker(10);
+		assert!(matches!(submitted(&pool_state).as_slice(), [crate::Call::commit_unsigned(..)]));
+	});
+}
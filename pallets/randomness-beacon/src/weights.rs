@@ -0,0 +1,69 @@
+//! Weights for pallet_randomness_beacon
+//!
+//! Commits and reveals cannot be benchmarked with the pallet alone, since they need the keys of a
+//! current Aura authority. These weights are estimated from the cost of verifying an sr25519
+//! signature, or hashing a seed, plus the storage accessed by the calls. Missed reveals include
+//! the storage accessed by `pallet_offences` and by an `OnOffenceHandler` removing the offender,
+//! such as `pallet_validator_set`.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_randomness_beacon.
+pub trait WeightInfo {
+	fn commit() -> Weight;
+	fn reveal() -> Weight;
+	fn close_round(c: u32, m: u32, ) -> Weight;
+}
+
+/// Weights for pallet_randomness_beacon using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn commit() -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn reveal() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn close_round(c: u32, m: u32, ) -> Weight {
+		(3_000_000 as Weight)
+			.saturating_add((2_500_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((15_000_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().reads((8 as Weight).saturating_mul(m as Weight)))
+			.saturating_add(T::DbWeight::get().writes((8 as Weight).saturating_mul(m as Weight)))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn commit() -> Weight {
+		(58_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn reveal() -> Weight {
+		(21_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn close_round(c: u32, m: u32, ) -> Weight {
+		(3_000_000 as Weight)
+			.saturating_add((2_500_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((15_000_000 as Weight).saturating_mul(m as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((8 as Weight).saturating_mul(m as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((8 as Weight).saturating_mul(m as Weight)))
+	}
+}
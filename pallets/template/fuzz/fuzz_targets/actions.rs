@@ -0,0 +1,13 @@
+//! Dispatch arbitrary sequences of calls against the mock runtime, see
+//! `pallet_template::fuzzing`.
+//!
+//! Run with `cargo fuzz run actions` from `pallets/template`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use pallet_template::fuzzing::{actions_from_bytes, run};
+
+fuzz_target!(|data: &[u8]| {
+	run(&actions_from_bytes(data));
+});
@@ -0,0 +1,88 @@
+//! RPC interface for the template pallet.
+
+use std::sync::Arc;
+use codec::Codec;
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+pub use pallet_template_rpc_runtime_api::{Stats, TemplateApi as TemplateRuntimeApi};
+
+#[rpc]
+pub trait TemplateApi<BlockHash, AccountId> {
+	/// Returns the value stored by `who` at the given block (or the best block).
+	#[rpc(name = "template_getValue")]
+	fn get_value(&self, who: AccountId, at: Option<BlockHash>) -> Result<Option<u32>>;
+
+	/// Returns aggregate statistics over all stored values at the given block (or the best block).
+	#[rpc(name = "template_getStats")]
+	fn get_stats(&self, at: Option<BlockHash>) -> Result<Stats>;
+}
+
+/// A struct that implements the [`TemplateApi`].
+pub struct Template<C, P> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> Template<C, P> {
+	/// Create new `Template` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+fn runtime_error(e: impl std::fmt::Debug) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(Error::RuntimeError.into()),
+		message: "Unable to query template state.".into(),
+		data: Some(format!("{:?}", e).into()),
+	}
+}
+
+impl<C, Block, AccountId> TemplateApi<<Block as BlockT>::Hash, AccountId> for Template<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: TemplateRuntimeApi<Block, AccountId>,
+	AccountId: Codec,
+{
+	fn get_value(
+		&self,
+		who: AccountId,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> Result<Option<u32>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.get_value(&at, who).map_err(runtime_error)
+	}
+
+	fn get_stats(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Stats> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.get_stats(&at).map_err(runtime_error)
+	}
+}
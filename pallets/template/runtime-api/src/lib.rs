@@ -0,0 +1,18 @@
+//! Runtime API definition for the template pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+pub use pallet_template::Stats;
+
+sp_api::decl_runtime_apis! {
+	pub trait TemplateApi<AccountId> where
+		AccountId: Codec,
+	{
+		/// The value stored by `who`, if any.
+		fn get_value(who: AccountId) -> Option<u32>;
+		/// Aggregate statistics over all stored values.
+		fn get_stats() -> Stats;
+	}
+}
@@ -0,0 +1,109 @@
+//! Benchmarking setup for pallet-template
+
+use super::*;
+
+use frame_system::RawOrigin;
+use frame_support::traits::{Currency, EnsureOrigin};
+use sp_runtime::traits::{Bounded, Zero};
+use frame_benchmarking::{account, benchmarks, whitelisted_caller, impl_benchmark_test_suite};
+#[allow(unused)]
+use crate::Pallet as Template;
+
+const SEED: u32 = 0;
+
+fn funded_caller<T: Config>() -> T::AccountId {
+	let caller: T::AccountId = whitelisted_caller();
+	T::Currency::make_free_balance_be(&caller, BalanceOf::<T>::max_value());
+	caller
+}
+
+fn set_value<T: Config>(who: &T::AccountId, value: u32) {
+	Something::<T>::insert(who, ValueInfo { value, updated_at: Zero::zero() });
+}
+
+benchmarks! {
+	// Storing the first value is the worst case, as it also reserves the deposit.
+	do_something {
+		let caller = funded_caller::<T>();
+	}: _(RawOrigin::Signed(caller.clone()), 42)
+	verify {
+		assert_eq!(Template::<T>::value_of(&caller), Some(42));
+		assert_eq!(Template::<T>::deposit_of(&caller), Some(T::DepositPerItem::get()));
+	}
+
+	clear_something {
+		let caller = funded_caller::<T>();
+		Template::<T>::do_something(RawOrigin::Signed(caller.clone()).into(), 1)?;
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Template::<T>::value_of(&caller), None);
+		assert_eq!(Template::<T>::deposit_of(&caller), None);
+	}
+
+	increment {
+		let caller: T::AccountId = whitelisted_caller();
+		set_value::<T>(&caller, 1);
+	}: _(RawOrigin::Signed(caller.clone()), 41)
+	verify {
+		assert_eq!(Template::<T>::value_of(&caller), Some(42));
+	}
+
+	// Like `do_something`, rolling the first value also reserves the deposit.
+	roll {
+		let caller = funded_caller::<T>();
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(Template::<T>::value_of(&caller).is_some());
+		assert_eq!(Template::<T>::deposit_of(&caller), Some(T::DepositPerItem::get()));
+	}
+
+	cause_error {
+		let caller: T::AccountId = whitelisted_caller();
+		set_value::<T>(&caller, 41);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_eq!(Template::<T>::value_of(&caller), Some(42));
+	}
+
+	set_bounds {
+		let origin = T::AdminOrigin::successful_origin();
+	}: _(origin, 1, 100)
+	verify {
+		assert_eq!(Template::<T>::bounds(), (1, 100));
+	}
+
+	force_set {
+		let origin = T::AdminOrigin::successful_origin();
+		let who: T::AccountId = account("who", 0, SEED);
+	}: _(origin, who.clone(), 42)
+	verify {
+		assert_eq!(Template::<T>::value_of(&who), Some(42));
+	}
+
+	// Every queued value has a deposit to release.
+	expire {
+		let n in 0 .. T::MaxExpiriesPerBlock::get();
+
+		let now = <frame_system::Module<T>>::block_number();
+		let at = Template::<T>::expires_at(now).unwrap_or_else(T::Period::get);
+		for i in 0 .. n {
+			let who: T::AccountId = account("who", i, SEED);
+			T::Currency::make_free_balance_be(&who, BalanceOf::<T>::max_value());
+			Template::<T>::do_something(RawOrigin::Signed(who).into(), i)?;
+		}
+		NextSweep::<T>::put(at);
+	}: {
+		Template::<T>::expire_due(at);
+	}
+	verify {
+		if !T::Ttl::get().is_zero() {
+			assert_eq!(Template::<T>::stats().entries, 0);
+		}
+	}
+}
+
+impl_benchmark_test_suite!(
+	Template,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
@@ -0,0 +1,239 @@
+//! A randomised test harness for the template pallet on the mock runtime.
+//!
+//! Sequences of [`Action`]s are decoded from raw bytes by [`actions_from_bytes`], so the same
+//! generator drives both the seeded property tests in `tests.rs` (see [`random_actions`]) and
+//! the `cargo fuzz` target in `fuzz/`. [`run`] dispatches the actions one by one and checks the
+//! pallet against a simple reference model after every step, panicking on any mismatch.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use frame_support::{dispatch::DispatchError, traits::Get};
+
+use crate::{mock::*, Error, Event as TemplateEvent, Expiries, QueuedSweep};
+
+type BalancesError = pallet_balances::Error<Test, pallet_balances::DefaultInstance>;
+
+/// The accounts actions are dispatched from. All but the last one are endowed, so the last
+/// one can never afford a deposit.
+pub const ACCOUNTS: [u64; 4] = [1, 2, 3, 4];
+
+/// Upper bound on the number of actions decoded from one input.
+pub const MAX_ACTIONS: usize = 256;
+
+/// Number of bytes [`actions_from_bytes`] consumes per action.
+const ACTION_LEN: usize = 6;
+
+/// A call dispatched by the harness.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Action {
+	DoSomething(u64, u32),
+	CauseError(u64),
+	Increment(u64, u32),
+	ClearSomething(u64),
+	/// Dispatched from root.
+	SetBounds(u32, u32),
+	/// Dispatched from root.
+	ForceSet(u64, u32),
+}
+
+/// Decode a sequence of actions, six bytes each; trailing bytes are ignored.
+///
+/// Values are biased towards `0` and `u32::max_value()` so the overflow boundary is hit often.
+pub fn actions_from_bytes(data: &[u8]) -> Vec<Action> {
+	data.chunks_exact(ACTION_LEN)
+		.take(MAX_ACTIONS)
+		.map(|chunk| {
+			let who = ACCOUNTS[chunk[1] as usize % ACCOUNTS.len()];
+			let raw = u32::from_le_bytes([chunk[2], chunk[3], chunk[4], chunk[5]]);
+			let value = match (chunk[0] >> 3) % 4 {
+				0 => raw,
+				1 => u32::max_value(),
+				2 => u32::max_value() - raw % 4,
+				_ => raw % 4,
+			};
+			match chunk[0] % 8 {
+				0 | 1 => Action::DoSomething(who, value),
+				2 => Action::CauseError(who),
+				3 => Action::Increment(who, value),
+				4 => Action::ClearSomething(who),
+				5 => Action::ForceSet(who, value),
+				// Mostly valid bounds, but sometimes `max < min`.
+				_ => Action::SetBounds(value, match chunk[1] % 4 {
+					0 => u32::max_value(),
+					1 => value.saturating_add(chunk[1] as u32),
+					2 => value,
+					_ => value.wrapping_sub(1),
+				}),
+			}
+		})
+		.collect()
+}
+
+/// Generate `len` actions from `seed`, deterministically.
+pub fn random_actions(seed: u64, len: usize) -> Vec<Action> {
+	// xorshift64*, seeded away from its zero fixed point.
+	let mut state = seed ^ 0x9E37_79B9_7F4A_7C15;
+	let bytes = (0..len * ACTION_LEN)
+		.map(|_| {
+			state ^= state >> 12;
+			state ^= state << 25;
+			state ^= state >> 27;
+			(state.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 56) as u8
+		})
+		.collect::<Vec<_>>();
+	actions_from_bytes(&bytes)
+}
+
+/// The expected state of the pallet.
+struct Model {
+	values: BTreeMap<u64, u32>,
+	/// The accounts that hold a deposit.
+	deposits: BTreeSet<u64>,
+	bounds: (u32, u32),
+}
+
+impl Default for Model {
+	fn default() -> Self {
+		Model {
+			values: Default::default(),
+			deposits: Default::default(),
+			bounds: (DefaultMinValue::get(), DefaultMaxValue::get()),
+		}
+	}
+}
+
+impl Model {
+	/// Apply `action`, returning the event it should deposit or the error it should fail with.
+	fn apply(&mut self, action: Action) -> Result<TemplateEvent<Test>, DispatchError> {
+		match action {
+			Action::DoSomething(who, value) => {
+				self.check_bounds(value)?;
+				let old = self.values.get(&who).copied();
+				if old.is_none() {
+					if !ENDOWED.contains(&who) {
+						return Err(BalancesError::InsufficientBalance.into());
+					}
+					self.deposits.insert(who);
+				}
+				self.values.insert(who, value);
+				Ok(TemplateEvent::SomethingStored(who, old, value))
+			},
+			Action::CauseError(who) => self.increment(who, 1),
+			Action::Increment(who, by) => self.increment(who, by),
+			Action::ClearSomething(who) => {
+				let old = self.values.remove(&who).ok_or(Error::<Test>::NoneValue)?;
+				self.deposits.remove(&who);
+				Ok(TemplateEvent::SomethingCleared(who, old))
+			},
+			Action::SetBounds(min, max) => {
+				if min > max {
+					return Err(Error::<Test>::InvalidBounds.into());
+				}
+				self.bounds = (min, max);
+				Ok(TemplateEvent::BoundsSet(min, max))
+			},
+			Action::ForceSet(who, value) => {
+				let old = self.values.insert(who, value);
+				Ok(TemplateEvent::SomethingForced(who, old, value))
+			},
+		}
+	}
+
+	fn increment(&mut self, who: u64, by: u32) -> Result<TemplateEvent<Test>, DispatchError> {
+		let old = *self.values.get(&who).ok_or(Error::<Test>::NoneValue)?;
+		let new = old.checked_add(by).ok_or(Error::<Test>::StorageOverflow)?;
+		self.check_bounds(new)?;
+		self.values.insert(who, new);
+		Ok(TemplateEvent::SomethingIncremented(who, old, new))
+	}
+
+	fn check_bounds(&self, value: u32) -> Result<(), DispatchError> {
+		let (min, max) = self.bounds;
+		if value < min {
+			return Err(Error::<Test>::ValueTooLow.into());
+		}
+		if value > max {
+			return Err(Error::<Test>::ValueTooHigh.into());
+		}
+		Ok(())
+	}
+}
+
+fn dispatch(action: Action) -> Result<(), DispatchError> {
+	let result = match action {
+		Action::DoSomething(who, value) => TemplateModule::do_something(Origin::signed(who), value),
+		Action::CauseError(who) => TemplateModule::cause_error(Origin::signed(who)),
+		Action::Increment(who, by) => TemplateModule::increment(Origin::signed(who), by),
+		Action::ClearSomething(who) => TemplateModule::clear_something(Origin::signed(who)),
+		Action::SetBounds(min, max) => TemplateModule::set_bounds(Origin::root(), min, max),
+		Action::ForceSet(who, value) => TemplateModule::force_set(Origin::root(), who, value),
+	};
+	result.map(|_| ()).map_err(|e| e.error)
+}
+
+/// Dispatch `actions` on a fresh mock runtime, checking the invariants after each of them.
+pub fn run(actions: &[Action]) {
+	new_test_ext().execute_with(|| {
+		let mut model = Model::default();
+
+		for (step, action) in actions.iter().enumerate() {
+			let events_before = System::events().len();
+			let expected = model.apply(*action);
+
+			assert_eq!(
+				dispatch(*action),
+				expected.as_ref().map(|_| ()).map_err(|e| *e),
+				"step {}: {:?}",
+				step,
+				action,
+			);
+			match expected {
+				// Successful calls end with exactly their own event...
+				Ok(event) => assert_eq!(
+					System::events().pop().map(|record| record.event),
+					Some(Event::pallet_template(event)),
+					"step {}: {:?}",
+					step,
+					action,
+				),
+				// ...and failed ones deposit nothing.
+				Err(_) => assert_eq!(
+					System::events().len(),
+					events_before,
+					"step {}: {:?}",
+					step,
+					action,
+				),
+			}
+
+			check_storage(&model, step);
+		}
+	});
+}
+
+/// Check that storage, deposits, bounds, stats and the expiry queue match `model`.
+fn check_storage(model: &Model, step: usize) {
+	for who in ACCOUNTS.iter() {
+		let expected = model.values.get(who).copied();
+		assert_eq!(TemplateModule::value_of(who), expected, "step {}: value of {}", step, who);
+
+		let deposit = if model.deposits.contains(who) { DepositPerItem::get() } else { 0 };
+		assert_eq!(Balances::reserved_balance(who), deposit, "step {}: deposit of {}", step, who);
+	}
+
+	assert_eq!(TemplateModule::bounds(), model.bounds, "step {}", step);
+
+	let stats = TemplateModule::stats();
+	assert_eq!(stats.entries as usize, model.values.len(), "step {}", step);
+	assert_eq!(stats.total, model.values.values().map(|v| *v as u64).sum::<u64>(), "step {}", step);
+	assert_eq!(stats.highest, model.values.values().max().copied(), "step {}", step);
+
+	// Every value is queued for expiry exactly once, at the block recorded for it.
+	for (at, who, ()) in Expiries::<Test>::iter() {
+		assert_eq!(QueuedSweep::<Test>::get(who), Some(at), "step {}: expiry of {}", step, who);
+	}
+	let mut queued = Expiries::<Test>::iter().map(|(_, who, ())| who).collect::<Vec<_>>();
+	queued.sort();
+	assert_eq!(queued, model.values.keys().copied().collect::<Vec<_>>(), "step {}", step);
+	assert_eq!(QueuedSweep::<Test>::iter().count(), queued.len(), "step {}", step);
+}
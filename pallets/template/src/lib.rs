@@ -6,22 +6,139 @@
 
 pub use pallet::*;
 
-#[cfg(test)]
-mod mock;
+use codec::{Encode, Decode};
+use frame_support::RuntimeDebug;
+#[cfg(feature = "std")]
+use serde::{Serialize, Deserialize};
+
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod mock;
+
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod fuzzing;
 
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+pub mod migrations;
+
+mod offchain;
+pub use offchain::{crypto, ValuePayload, KEY_TYPE, DEFAULT_ENDPOINT, ENDPOINT_KEY, SUBMISSION_KEY};
+
+/// The `InvalidTransaction::Custom` code of unsigned submissions whose value is out of `Bounds`.
+pub const VALUE_OUT_OF_BOUNDS: u8 = 1;
+
+/// Aggregate information about the values stored in the pallet, as reported by the runtime API.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Stats {
+	/// Number of accounts that have a value stored.
+	pub entries: u32,
+	/// Sum of all stored values.
+	pub total: u64,
+	/// The highest stored value, if any.
+	pub highest: Option<u32>,
+}
+
+/// A value stored in the pallet. The account owning it is the key it is stored under.
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct ValueInfo<BlockNumber> {
+	/// The stored value.
+	pub value: u32,
+	/// The block in which the value was last written.
+	pub updated_at: BlockNumber,
+}
+
+/// Storage layouts of the pallet, used to decide which migrations need to run.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum Releases {
+	/// `Something` maps each account to a bare `u32`.
+	V1,
+	/// `Something` maps each account to a [`ValueInfo`].
+	V2,
+	/// `Expiries` holds one entry per value, keyed by its sweep block and account, and
+	/// `QueuedSweep` records that block.
+	V3,
+}
+
+impl Default for Releases {
+	fn default() -> Self {
+		Releases::V1
+	}
+}
+
 #[frame_support::pallet]
 pub mod pallet {
-	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
-	use frame_system::pallet_prelude::*;
+	use frame_support::{
+		dispatch::DispatchResultWithPostInfo,
+		pallet_prelude::*,
+		traits::{Contains, Currency, Randomness, ReservableCurrency},
+	};
+	use frame_system::{
+		pallet_prelude::*,
+		offchain::{AppCrypto, CreateSignedTransaction, SignedPayload},
+	};
+	use sp_runtime::traits::{IdentifyAccount, TrailingZeroInput, Zero};
+	use sp_std::prelude::*;
+	use super::{Decode, Encode, WeightInfo, ValuePayload, ValueInfo, Releases};
+
+	pub(crate) type BalanceOf<T> =
+		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 	/// Configure the pallet by specifying the parameters and types on which it depends.
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: CreateSignedTransaction<Call<Self>> + frame_system::Config {
+		/// The identifier type for the off-chain worker's keys.
+		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
 		/// Because this pallet emits events, it depends on the runtime's definition of an event.
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The currency in which storage deposits are held.
+		type Currency: ReservableCurrency<Self::AccountId>;
+		/// The amount reserved from an account while it has a value stored.
+		#[pallet::constant]
+		type DepositPerItem: Get<BalanceOf<Self>>;
+		/// Number of blocks the off-chain worker waits after a submission before it fetches a
+		/// new value.
+		#[pallet::constant]
+		type GracePeriod: Get<Self::BlockNumber>;
+		/// Minimum number of blocks between two accepted unsigned submissions.
+		#[pallet::constant]
+		type UnsignedInterval: Get<Self::BlockNumber>;
+		/// The accounts whose off-chain worker keys may submit unsigned values.
+		type Feeders: Contains<Self::AccountId>;
+		/// Transaction pool priority of unsigned submissions.
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+		/// Number of blocks after its last update at which a value expires and its deposit is
+		/// returned. Zero disables expiry.
+		#[pallet::constant]
+		type Ttl: Get<Self::BlockNumber>;
+		/// Values expire at multiples of `Period` blocks. Must not be zero.
+		#[pallet::constant]
+		type Period: Get<Self::BlockNumber>;
+		/// The maximum number of values expired in a block; the rest are expired in the following
+		/// blocks.
+		#[pallet::constant]
+		type MaxExpiriesPerBlock: Get<u32>;
+		/// The origin allowed to change the bounds and to force values.
+		type AdminOrigin: EnsureOrigin<Self::Origin>;
+		/// The smallest value accounts may store until the bounds are changed with `set_bounds`.
+		#[pallet::constant]
+		type DefaultMinValue: Get<u32>;
+		/// The largest value accounts may store until the bounds are changed with `set_bounds`.
+		#[pallet::constant]
+		type DefaultMaxValue: Get<u32>;
+		/// The source of the values stored by `roll`.
+		type Randomness: Randomness<Self::Hash>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::pallet]
@@ -34,7 +151,86 @@ pub mod pallet {
 	#[pallet::getter(fn something)]
 	// Learn more about declaring storage items:
 	// https://substrate.dev/docs/en/knowledgebase/runtime/storage#declaring-storage-items
-	pub type Something<T> = StorageValue<_, u32>;
+	/// The value stored by each account.
+	pub type Something<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, ValueInfo<T::BlockNumber>>;
+
+	/// The deposit reserved for each stored value.
+	///
+	/// Values that predate storage deposits have no entry here.
+	#[pallet::storage]
+	#[pallet::getter(fn deposit_of)]
+	pub type Deposits<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>>;
+
+	/// The accounts whose values are due to expire, keyed by the sweep block.
+	///
+	/// Every value has a single entry, which is moved when the value is updated and removed when
+	/// it is cleared.
+	#[pallet::storage]
+	pub type Expiries<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, T::BlockNumber, Blake2_128Concat, T::AccountId, ()>;
+
+	/// The sweep block each value is queued for in `Expiries`.
+	///
+	/// This is kept rather than computed again from `updated_at`, since the entry may be queued
+	/// elsewhere: the V3 migration queues overdue values for the next sweep, and a change of `Ttl`
+	/// moves the block a value would be queued for.
+	#[pallet::storage]
+	#[pallet::getter(fn queued_sweep)]
+	pub type QueuedSweep<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, T::BlockNumber>;
+
+	/// The oldest sweep block that may still have entries in `Expiries`.
+	#[pallet::storage]
+	#[pallet::getter(fn next_sweep)]
+	pub type NextSweep<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	#[pallet::type_value]
+	pub fn DefaultBounds<T: Config>() -> (u32, u32) {
+		(T::DefaultMinValue::get(), T::DefaultMaxValue::get())
+	}
+
+	/// The inclusive `(min, max)` range of values accounts may store.
+	#[pallet::storage]
+	#[pallet::getter(fn bounds)]
+	pub type Bounds<T: Config> = StorageValue<_, (u32, u32), ValueQuery, DefaultBounds<T>>;
+
+	/// The storage layout currently in use.
+	///
+	/// New networks start with the latest layout; for existing networks that predate this item
+	/// it defaults to `Releases::V1`.
+	#[pallet::storage]
+	pub type StorageVersion<T> = StorageValue<_, Releases, ValueQuery>;
+
+	/// The block from which the next unsigned submission is accepted.
+	#[pallet::storage]
+	#[pallet::getter(fn next_unsigned_at)]
+	pub type NextUnsignedAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The values stored at genesis, keyed by the account that owns them.
+		pub something: Vec<(T::AccountId, u32)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { something: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (who, value) in &self.something {
+				Pallet::<T>::reserve_deposit(who)
+					.expect("genesis accounts must be able to afford the deposit; qed");
+				<Something<T>>::insert(who, ValueInfo { value: *value, updated_at: Zero::zero() });
+				Pallet::<T>::queue_expiry(who, Pallet::<T>::expires_at(Zero::zero()));
+			}
+			<StorageVersion<T>>::put(Releases::V3);
+		}
+	}
 
 	// Pallets use events to inform users when important changes are made.
 	// https://substrate.dev/docs/en/knowledgebase/runtime/events
@@ -43,10 +239,20 @@ pub mod pallet {
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// Event documentation should end with an array that provides descriptive names for event
-		/// parameters. [something, who]
-		SomethingStored(u32, T::AccountId),
+		/// parameters. [who, old, new]
+		SomethingStored(T::AccountId, Option<u32>, u32),
+		/// An account removed its value. [who, old]
+		SomethingCleared(T::AccountId, u32),
+		/// An account's value was incremented. [who, old, new]
+		SomethingIncremented(T::AccountId, u32, u32),
+		/// An account's value expired and its deposit was returned. [who, old]
+		SomethingExpired(T::AccountId, u32),
+		/// The bounds on stored values were changed. [min, max]
+		BoundsSet(u32, u32),
+		/// The admin origin set an account's value. [who, old, new]
+		SomethingForced(T::AccountId, Option<u32>, u32),
 	}
-	
+
 	// Errors inform users that something went wrong.
 	#[pallet::error]
 	pub enum Error<T> {
@@ -54,51 +260,427 @@ pub mod pallet {
 		NoneValue,
 		/// Errors should have helpful documentation associated with them.
 		StorageOverflow,
+		/// The value is below the minimum set in `Bounds`.
+		ValueTooLow,
+		/// The value is above the maximum set in `Bounds`.
+		ValueTooHigh,
+		/// The minimum is greater than the maximum.
+		InvalidBounds,
 	}
 
 	#[pallet::hooks]
-	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Remove the values that are due to expire, at most `MaxExpiriesPerBlock` of them.
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			Self::expire_due(now)
+		}
+
+		/// Fetch a value from the configured HTTP endpoint and submit it back on-chain.
+		///
+		/// See the `offchain` module for how the endpoint and the submission mode are configured.
+		fn offchain_worker(block_number: T::BlockNumber) {
+			Self::run_offchain_worker(block_number);
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v2::migrate::<T>()
+				.saturating_add(crate::migrations::v3::migrate::<T>())
+		}
+
+		fn integrity_test() {
+			assert!(!T::Period::get().is_zero(), "`Period` must not be zero");
+
+			let max_block = T::BlockWeights::get().max_block;
+			let max = T::MaxExpiriesPerBlock::get();
+			let sweep = T::WeightInfo::expire(max)
+				.saturating_add(T::DbWeight::get().reads(max.into()));
+			assert!(
+				sweep < max_block,
+				"a sweep of `MaxExpiriesPerBlock` values ({}) must fit in a block ({})",
+				sweep,
+				max_block,
+			);
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			if <StorageVersion<T>>::get() == Releases::V1 {
+				crate::migrations::v2::pre_migrate::<T>()?;
+			}
+			crate::migrations::v3::pre_migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			crate::migrations::v3::post_migrate::<T>()
+		}
+	}
 
 	// Dispatchable functions allows users to interact with the pallet and invoke state changes.
 	// These functions materialize as "extrinsics", which are often compared to transactions.
 	// Dispatchable functions must be annotated with a weight and must return a DispatchResult.
+	// The weights come from `weights.rs`, which is meant to be regenerated from the benchmarks in
+	// `benchmarking.rs`.
 	#[pallet::call]
 	impl<T:Config> Pallet<T> {
 		/// An example dispatchable that takes a singles value as a parameter, writes the value to
-		/// storage and emits an event. This function must be dispatched by a signed extrinsic.
-		#[pallet::weight(10_000 + T::DbWeight::get().writes(1))]
+		/// the signer's storage entry and emits an event. This function must be dispatched by a
+		/// signed extrinsic.
+		#[pallet::weight(T::WeightInfo::do_something())]
 		pub fn do_something(origin: OriginFor<T>, something: u32) -> DispatchResultWithPostInfo {
 			// Check that the extrinsic was signed and get the signer.
 			// This function will return an error if the extrinsic is not signed.
 			// https://substrate.dev/docs/en/knowledgebase/runtime/origin
 			let who = ensure_signed(origin)?;
 
-			// Update storage.
-			<Something<T>>::put(something);
-
-			// Emit an event.
-			Self::deposit_event(Event::SomethingStored(something, who));
+			// Update storage and emit an event.
+			Self::store_value(who, something)?;
 			// Return a successful DispatchResultWithPostInfo
 			Ok(().into())
 		}
 
+		/// Remove the signer's value from storage, returning its deposit.
+		#[pallet::weight(T::WeightInfo::clear_something())]
+		pub fn clear_something(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let old = <Something<T>>::take(&who).ok_or(Error::<T>::NoneValue)?;
+			Self::release_deposit(&who);
+			Self::queue_expiry(&who, None);
+
+			Self::deposit_event(Event::SomethingCleared(who, old.value));
+			Ok(().into())
+		}
+
+		/// Add `by` to the signer's value.
+		#[pallet::weight(T::WeightInfo::increment())]
+		pub fn increment(origin: OriginFor<T>, by: u32) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			Self::try_increment(who, by)?;
+			Ok(().into())
+		}
+
+		/// Store a random value within the bounds for the signer.
+		#[pallet::weight(T::WeightInfo::roll())]
+		pub fn roll(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let who = ensure_signed(origin)?;
+
+			let value = Self::random_in_bounds(&who);
+			Self::store_value(who, value)?;
+			Ok(().into())
+		}
+
 		/// An example dispatchable that may throw a custom error.
-		#[pallet::weight(10_000 + T::DbWeight::get().reads_writes(1,1))]
+		#[pallet::weight(T::WeightInfo::cause_error())]
 		pub fn cause_error(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
-			let _who = ensure_signed(origin)?;
+			let who = ensure_signed(origin)?;
+
+			Self::try_increment(who, 1)?;
+			Ok(().into())
+		}
+
+		/// Store a value fetched by an off-chain worker for the account behind `payload.public`.
+		///
+		/// This is an unsigned transaction; its authenticity is checked by verifying `signature`
+		/// over `payload` in `validate_unsigned`, which also enforces `UnsignedInterval` and that
+		/// the signer is one of the `Feeders`.
+		///
+		/// `NextUnsignedAt` is bumped before the value is stored. As this call is not
+		/// transactional, it stays bumped if storing fails, so a failing submission cannot be
+		/// resubmitted within the same interval.
+		#[pallet::weight(
+			// Same storage access as `do_something`, plus updating `NextUnsignedAt`.
+			T::WeightInfo::do_something().saturating_add(T::DbWeight::get().reads_writes(1, 1))
+		)]
+		pub fn submit_value_unsigned(
+			origin: OriginFor<T>,
+			payload: ValuePayload<T::Public, T::BlockNumber>,
+			_signature: T::Signature,
+		) -> DispatchResultWithPostInfo {
+			// This ensures that the function can only be called via unsigned transaction.
+			ensure_none(origin)?;
+
+			let current_block = <frame_system::Module<T>>::block_number();
+			<NextUnsignedAt<T>>::put(current_block + T::UnsignedInterval::get());
 
+			Self::store_value(payload.public.into_account(), payload.value)?;
+			Ok(().into())
+		}
+
+		/// Change the range of values accounts may store.
+		///
+		/// Values already stored are not affected. The dispatch origin must be `AdminOrigin`.
+		#[pallet::weight(T::WeightInfo::set_bounds())]
+		pub fn set_bounds(origin: OriginFor<T>, min: u32, max: u32) -> DispatchResultWithPostInfo {
+			T::AdminOrigin::ensure_origin(origin)?;
+			ensure!(min <= max, Error::<T>::InvalidBounds);
+
+			<Bounds<T>>::put((min, max));
+			Self::deposit_event(Event::BoundsSet(min, max));
+			Ok(().into())
+		}
+
+		/// Set the value of `who`, ignoring the bounds.
+		///
+		/// No deposit is reserved for values created this way. The dispatch origin must be
+		/// `AdminOrigin`.
+		#[pallet::weight(T::WeightInfo::force_set())]
+		pub fn force_set(
+			origin: OriginFor<T>,
+			who: T::AccountId,
+			value: u32,
+		) -> DispatchResultWithPostInfo {
+			T::AdminOrigin::ensure_origin(origin)?;
+
+			let now = <frame_system::Module<T>>::block_number();
+			let old = <Something<T>>::mutate(&who, |stored| {
+				stored.replace(ValueInfo { value, updated_at: now })
+			});
+			Self::queue_expiry(&who, Self::expires_at(now));
+			Self::deposit_event(Event::SomethingForced(who, old.map(|info| info.value), value));
+			Ok(().into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		/// Accept unsigned submissions that carry a valid signature from the key of one of the
+		/// `Feeders` and respect the `UnsignedInterval` rate limit.
+		///
+		/// Submissions that would fail to store their value are rejected here as well, so that
+		/// they do not take the slot of the interval: the value must be within `Bounds` and the
+		/// signer must be able to afford the deposit of a new value.
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if let Call::submit_value_unsigned(ref payload, ref signature) = call {
+				let signature_valid =
+					SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone());
+				if !signature_valid {
+					return InvalidTransaction::BadProof.into();
+				}
+				let who = payload.public.clone().into_account();
+				if !T::Feeders::contains(&who) {
+					return InvalidTransaction::BadSigner.into();
+				}
+
+				let next_unsigned_at = <NextUnsignedAt<T>>::get();
+				if next_unsigned_at > payload.block_number {
+					return InvalidTransaction::Stale.into();
+				}
+				let current_block = <frame_system::Module<T>>::block_number();
+				if current_block < payload.block_number {
+					return InvalidTransaction::Future.into();
+				}
+
+				if Self::ensure_in_bounds(payload.value).is_err() {
+					return InvalidTransaction::Custom(super::VALUE_OUT_OF_BOUNDS).into();
+				}
+				if !<Something<T>>::contains_key(&who) &&
+					!T::Currency::can_reserve(&who, T::DepositPerItem::get())
+				{
+					return InvalidTransaction::Payment.into();
+				}
+
+				ValidTransaction::with_tag_prefix("TemplateOffchainWorker")
+					.priority(T::UnsignedPriority::get())
+					// Only one submission per interval can make it into the pool.
+					.and_provides(next_unsigned_at)
+					.longevity(5)
+					.propagate(true)
+					.build()
+			} else {
+				InvalidTransaction::Call.into()
+			}
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Collect the [`Stats`](super::Stats) of all stored values.
+		///
+		/// This iterates the whole of `Something` and is meant to be called from the runtime API
+		/// only, never from a dispatchable.
+		pub fn stats() -> super::Stats {
+			<Something<T>>::iter_values().fold(Default::default(), |mut stats: super::Stats, info| {
+				let value = info.value;
+				stats.entries = stats.entries.saturating_add(1);
+				stats.total = stats.total.saturating_add(value.into());
+				stats.highest = stats.highest.max(Some(value));
+				stats
+			})
+		}
+
+		/// The value stored by `who`, if any.
+		pub fn value_of(who: &T::AccountId) -> Option<u32> {
+			<Something<T>>::get(who).map(|info| info.value)
+		}
+
+		/// Store `value` for `who`, reporting the previous value in the event.
+		///
+		/// The first value stored by an account reserves `DepositPerItem` from it.
+		fn store_value(who: T::AccountId, value: u32) -> DispatchResult {
+			Self::ensure_in_bounds(value)?;
+			if !<Something<T>>::contains_key(&who) {
+				Self::reserve_deposit(&who)?;
+			}
+
+			let now = <frame_system::Module<T>>::block_number();
+			let info = ValueInfo { value, updated_at: now };
+			let old = <Something<T>>::mutate(&who, |stored| stored.replace(info));
+			Self::queue_expiry(&who, Self::expires_at(now));
+			Self::deposit_event(Event::SomethingStored(who, old.map(|info| info.value), value));
+			Ok(())
+		}
+
+		fn reserve_deposit(who: &T::AccountId) -> DispatchResult {
+			let deposit = T::DepositPerItem::get();
+			T::Currency::reserve(who, deposit)?;
+			<Deposits<T>>::insert(who, deposit);
+			Ok(())
+		}
+
+		/// Return whatever deposit `who` has reserved for its value.
+		fn release_deposit(who: &T::AccountId) {
+			if let Some(deposit) = <Deposits<T>>::take(who) {
+				let _ = T::Currency::unreserve(who, deposit);
+			}
+		}
+
+		/// Increment the value stored by `who`, failing if it is not set, would overflow or would
+		/// leave the bounds.
+		fn try_increment(who: T::AccountId, by: u32) -> DispatchResult {
 			// Read a value from storage.
-			match <Something<T>>::get() {
-				// Return an error if the value has not been set.
-				None => Err(Error::<T>::NoneValue)?,
-				Some(old) => {
-					// Increment the value read from storage; will error in the event of overflow.
-					let new = old.checked_add(1).ok_or(Error::<T>::StorageOverflow)?;
-					// Update the value in storage with the incremented result.
-					<Something<T>>::put(new);
-					Ok(().into())
-				},
+			let old = Self::value_of(&who).ok_or(Error::<T>::NoneValue)?;
+			// Increment the value read from storage; will error in the event of overflow.
+			let new = old.checked_add(by).ok_or(Error::<T>::StorageOverflow)?;
+			Self::ensure_in_bounds(new)?;
+			// Update the value in storage with the incremented result.
+			let updated_at = <frame_system::Module<T>>::block_number();
+			<Something<T>>::insert(&who, ValueInfo { value: new, updated_at });
+			Self::queue_expiry(&who, Self::expires_at(updated_at));
+
+			Self::deposit_event(Event::SomethingIncremented(who, old, new));
+			Ok(())
+		}
+
+		/// A value within the bounds, drawn from `Randomness` for `who`.
+		///
+		/// The account nonce is part of the subject, so `who` gets different values when rolling
+		/// several times in one block.
+		fn random_in_bounds(who: &T::AccountId) -> u32 {
+			let nonce = <frame_system::Module<T>>::account_nonce(who);
+			let subject = (b"template/roll", who, nonce).encode();
+			let random = T::Randomness::random(&subject);
+			let random = u64::decode(&mut TrailingZeroInput::new(random.as_ref()))
+				.expect("input is padded with zeroes; qed");
+
+			let (min, max) = <Bounds<T>>::get();
+			let span = u64::from(max - min) + 1;
+			min + (random % span) as u32
+		}
+
+		fn ensure_in_bounds(value: u32) -> DispatchResult {
+			let (min, max) = <Bounds<T>>::get();
+			ensure!(value >= min, Error::<T>::ValueTooLow);
+			ensure!(value <= max, Error::<T>::ValueTooHigh);
+			Ok(())
+		}
+
+		/// The sweep block at which a value updated at `updated_at` expires, if expiry is enabled.
+		///
+		/// This is the first multiple of `Period` at or after `updated_at + Ttl`.
+		pub fn expires_at(updated_at: T::BlockNumber) -> Option<T::BlockNumber> {
+			let ttl = T::Ttl::get();
+			if ttl.is_zero() {
+				return None;
+			}
+
+			Some(Self::sweep_at(updated_at.saturating_add(ttl)))
+		}
+
+		/// The first multiple of `Period` at or after `block`.
+		pub(crate) fn sweep_at(block: T::BlockNumber) -> T::BlockNumber {
+			let period = T::Period::get();
+			let rem = block % period;
+			if rem.is_zero() { block } else { block.saturating_add(period - rem) }
+		}
+
+		/// The accounts queued to expire at the sweep block `at`.
+		pub fn expiries(at: T::BlockNumber) -> Vec<T::AccountId> {
+			<Expiries<T>>::iter_prefix(at).map(|(who, ())| who).collect()
+		}
+
+		/// Queue the value of `who` to expire at the sweep block `at`, or not at all, replacing the
+		/// entry recorded in `QueuedSweep`, if any.
+		pub(crate) fn queue_expiry(who: &T::AccountId, at: Option<T::BlockNumber>) {
+			let queued = <QueuedSweep<T>>::get(who);
+			if queued == at {
+				return;
 			}
+			if let Some(queued) = queued {
+				<Expiries<T>>::remove(queued, who);
+			}
+			match at {
+				Some(at) => {
+					<Expiries<T>>::insert(at, who, ());
+					<QueuedSweep<T>>::insert(who, at);
+				}
+				None => <QueuedSweep<T>>::remove(who),
+			}
+		}
+
+		/// Expire the values queued for `NextSweep` and the sweep blocks after it up to `now`,
+		/// returning the weight consumed.
+		///
+		/// At most `MaxExpiriesPerBlock` values are expired and as many sweep blocks looked at, so
+		/// a backlog is worked off over the following blocks. A value whose last update is not due
+		/// by the sweep, because `Ttl` grew since it was queued, is queued again instead.
+		pub(crate) fn expire_due(now: T::BlockNumber) -> Weight {
+			let mut sweep = <NextSweep<T>>::get();
+			if sweep > now {
+				return T::DbWeight::get().reads(1);
+			}
+
+			let max = T::MaxExpiriesPerBlock::get();
+			let mut expired = 0u32;
+			let mut visited = 0u32;
+			while sweep <= now && visited < max {
+				visited += 1;
+				let due = <Expiries<T>>::iter_prefix(sweep)
+					.map(|(who, ())| who)
+					.take((max - expired) as usize)
+					.collect::<Vec<_>>();
+				for who in due {
+					<Expiries<T>>::remove(sweep, &who);
+					expired += 1;
+					if <QueuedSweep<T>>::get(&who) != Some(sweep) {
+						// Not the entry the value is queued under.
+						continue;
+					}
+					<QueuedSweep<T>>::remove(&who);
+					let info = match <Something<T>>::get(&who) {
+						Some(info) => info,
+						None => continue,
+					};
+					let due_at = Self::expires_at(info.updated_at);
+					if due_at.map_or(true, |at| at > sweep) {
+						Self::queue_expiry(&who, due_at);
+						continue;
+					}
+					<Something<T>>::remove(&who);
+					Self::release_deposit(&who);
+					Self::deposit_event(Event::SomethingExpired(who, info.value));
+				}
+				if expired == max {
+					// `sweep` may have entries left.
+					break;
+				}
+				sweep = sweep.saturating_add(T::Period::get());
+			}
+			<NextSweep<T>>::put(sweep);
+
+			T::WeightInfo::expire(expired).saturating_add(T::DbWeight::get().reads(visited.into()))
 		}
 	}
 }
@@ -0,0 +1,159 @@
+//! Storage migrations for the template pallet.
+//!
+//! Each layout change bumps [`Releases`](crate::Releases) and adds a module here that
+//! translates the previous layout. The migration runs from `on_runtime_upgrade` and only acts
+//! if `StorageVersion` still names the layout it migrates from, so it is safe to leave in place
+//! across further upgrades.
+
+/// Migrate `Something` from bare `u32` values to [`ValueInfo`](crate::ValueInfo).
+pub mod v2 {
+	use codec::Decode;
+	use frame_support::{
+		ensure,
+		storage::{unhashed, StoragePrefixedMap},
+		traits::Get,
+		weights::Weight,
+	};
+	use sp_std::prelude::*;
+	use crate::{Config, Releases, Something, StorageVersion, ValueInfo};
+
+	/// Translate every stored value, stamping it with the block of the upgrade.
+	///
+	/// This also removes the single global value kept by the original template pallet, which
+	/// lived at the bare `Something` prefix and has no owner to migrate it to.
+	///
+	/// Every value is translated in the block of the upgrade, so the cost grows with the number of
+	/// accounts that have a value. Each takes a read and a write, so about ten thousand entries fit
+	/// in a block with the RocksDB weights. The networks that predate V2 are development and test
+	/// networks with far fewer values; a network with many more would need a multi-block
+	/// migration instead.
+	pub fn migrate<T: Config>() -> Weight {
+		if StorageVersion::<T>::get() != Releases::V1 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let updated_at = <frame_system::Module<T>>::block_number();
+		let mut translated = 0u64;
+		Something::<T>::translate::<u32, _>(|_, value| {
+			translated += 1;
+			Some(ValueInfo { value, updated_at })
+		});
+		unhashed::kill(&Something::<T>::final_prefix());
+		StorageVersion::<T>::put(Releases::V2);
+
+		T::DbWeight::get().reads_writes(translated + 2, translated + 2)
+	}
+
+	/// Check that the storage is in the layout this migration expects.
+	#[cfg(any(feature = "try-runtime", test))]
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() == Releases::V1, "`Something` is not at V1");
+		for raw in raw_values::<T>() {
+			ensure!(u32::decode(&mut &raw[..]).is_ok() && raw.len() == 4, "undecodable V1 value");
+		}
+		Ok(())
+	}
+
+	/// Check that every value was translated.
+	#[cfg(any(feature = "try-runtime", test))]
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() == Releases::V2, "`StorageVersion` was not bumped");
+		ensure!(
+			unhashed::get_raw(&Something::<T>::final_prefix()).is_none(),
+			"the global value of the original pallet was not removed",
+		);
+		for raw in raw_values::<T>() {
+			let mut input = &raw[..];
+			ensure!(
+				ValueInfo::<T::BlockNumber>::decode(&mut input).is_ok() && input.is_empty(),
+				"undecodable V2 value",
+			);
+		}
+		Ok(())
+	}
+
+	/// The raw values stored under `Something`, whatever their layout.
+	#[cfg(any(feature = "try-runtime", test))]
+	fn raw_values<T: Config>() -> Vec<Vec<u8>> {
+		let prefix = Something::<T>::final_prefix();
+		let mut values = Vec::new();
+		let mut key = prefix.to_vec();
+		while let Some(next) = sp_io::storage::next_key(&key) {
+			if !next.starts_with(&prefix) {
+				break;
+			}
+			values.extend(unhashed::get_raw(&next));
+			key = next;
+		}
+		values
+	}
+}
+
+/// Rebuild `Expiries` with a single entry per value, keyed by its sweep block and account.
+pub mod v3 {
+	use frame_support::{
+		ensure,
+		storage::{unhashed, StoragePrefixedMap},
+		traits::Get,
+		weights::Weight,
+	};
+	use crate::{
+		Config, Expiries, NextSweep, Pallet, QueuedSweep, Releases, Something, StorageVersion,
+	};
+
+	/// Queue every stored value for expiry in the new layout.
+	///
+	/// The V2 queue kept a list of accounts per sweep block, stale ones included, so it is removed
+	/// rather than translated, and every value is queued again for the sweep block its last update
+	/// expires at. Values that are already overdue are queued for the next sweep. The block each
+	/// value is queued for is recorded in `QueuedSweep`, so that an update before that sweep finds
+	/// the entry to replace. Like the V2 migration, this goes over every value in the block of the
+	/// upgrade.
+	pub fn migrate<T: Config>() -> Weight {
+		if StorageVersion::<T>::get() != Releases::V2 {
+			return T::DbWeight::get().reads(1);
+		}
+
+		unhashed::kill_prefix(&Expiries::<T>::final_prefix());
+		unhashed::kill_prefix(&QueuedSweep::<T>::final_prefix());
+		let next_sweep = Pallet::<T>::sweep_at(<frame_system::Module<T>>::block_number());
+		let mut queued = 0u64;
+		for (who, info) in Something::<T>::iter() {
+			queued += 1;
+			let at = Pallet::<T>::expires_at(info.updated_at).map(|at| at.max(next_sweep));
+			Pallet::<T>::queue_expiry(&who, at);
+		}
+		NextSweep::<T>::put(next_sweep);
+		StorageVersion::<T>::put(Releases::V3);
+
+		T::DbWeight::get().reads_writes(2 * queued + 1, 2 * queued + 4)
+	}
+
+	/// Check that the storage is in a layout this migration, or the V2 one, expects.
+	#[cfg(any(feature = "try-runtime", test))]
+	pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() != Releases::V3, "`Expiries` is already at V3");
+		Ok(())
+	}
+
+	/// Check that every value that can expire is queued exactly once, at the block recorded in
+	/// `QueuedSweep`, and nothing else is.
+	#[cfg(any(feature = "try-runtime", test))]
+	pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+		ensure!(StorageVersion::<T>::get() == Releases::V3, "`StorageVersion` was not bumped");
+		let next_sweep = NextSweep::<T>::get();
+		let mut queued = 0usize;
+		for (at, who, ()) in Expiries::<T>::iter() {
+			queued += 1;
+			ensure!(at >= next_sweep, "an expiry is queued before `NextSweep`");
+			ensure!(Something::<T>::contains_key(&who), "an expiry is queued for no value");
+			ensure!(QueuedSweep::<T>::get(&who) == Some(at), "an expiry is not recorded");
+		}
+		let expiring = Something::<T>::iter_values()
+			.filter(|info| Pallet::<T>::expires_at(info.updated_at).is_some())
+			.count();
+		ensure!(queued == expiring, "not every value is queued exactly once");
+		ensure!(QueuedSweep::<T>::iter().count() == queued, "an expiry is recorded but not queued");
+		Ok(())
+	}
+}
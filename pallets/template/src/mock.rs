@@ -1,8 +1,10 @@
 use crate as pallet_template;
 use sp_core::H256;
-use frame_support::parameter_types;
+use frame_support::{parameter_types, traits::{Contains, GenesisBuild, Randomness}};
 use sp_runtime::{
-	traits::{BlakeTwo256, IdentityLookup}, testing::Header,
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, Hash, IdentityLookup},
+	testing::{Header, TestSignature, TestXt, UintAuthorityId},
+	transaction_validity::TransactionPriority,
 };
 use frame_system as system;
 
@@ -17,7 +19,7 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Module, Call, Config, Storage, Event<T>},
-		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>},
+		TemplateModule: pallet_template::{Module, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
 	}
 );
 
@@ -44,18 +46,128 @@ impl system::Config for Test {
 	type BlockHashCount = BlockHashCount;
 	type Version = ();
 	type PalletInfo = PalletInfo;
-	type AccountData = ();
+	type AccountData = pallet_balances::AccountData<u64>;
 	type OnNewAccount = ();
 	type OnKilledAccount = ();
 	type SystemWeightInfo = ();
 	type SS58Prefix = SS58Prefix;
 }
 
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type MaxLocks = ();
+	type Balance = u64;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test where
+	Call: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		_public: UintAuthorityId,
+		account: u64,
+		_nonce: u64,
+	) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (account, ())))
+	}
+}
+
+/// Off-chain worker keys backed by the `UintAuthorityId` test keystore, see
+/// `UintAuthorityId::set_all_keys`.
+pub struct TestAuthId;
+
+impl frame_system::offchain::AppCrypto<UintAuthorityId, TestSignature> for TestAuthId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+}
+
+parameter_types! {
+	pub const GracePeriod: u64 = 5;
+	pub const UnsignedInterval: u64 = 10;
+	pub const UnsignedPriority: TransactionPriority = 1 << 20;
+	pub const DepositPerItem: u64 = 10;
+	pub const Ttl: u64 = 20;
+	pub const Period: u64 = 5;
+	pub const MaxExpiriesPerBlock: u32 = 2;
+	pub const DefaultMinValue: u32 = 0;
+	pub const DefaultMaxValue: u32 = u32::max_value();
+}
+
+/// Hashes the subject, which is random enough for tests.
+pub struct TestRandomness;
+
+impl Randomness<H256> for TestRandomness {
+	fn random(subject: &[u8]) -> H256 {
+		BlakeTwo256::hash(subject)
+	}
+}
+
+/// The accounts whose off-chain worker keys may submit unsigned values.
+pub const FEEDERS: [u64; 2] = [7, 8];
+
+pub struct TestFeeders;
+
+impl Contains<u64> for TestFeeders {
+	fn sorted_members() -> Vec<u64> {
+		FEEDERS.to_vec()
+	}
+}
+
 impl pallet_template::Config for Test {
+	type AuthorityId = TestAuthId;
 	type Event = Event;
+	type Currency = Balances;
+	type DepositPerItem = DepositPerItem;
+	type GracePeriod = GracePeriod;
+	type UnsignedInterval = UnsignedInterval;
+	type Feeders = TestFeeders;
+	type UnsignedPriority = UnsignedPriority;
+	type Ttl = Ttl;
+	type Period = Period;
+	type MaxExpiriesPerBlock = MaxExpiriesPerBlock;
+	type AdminOrigin = frame_system::EnsureRoot<u64>;
+	type DefaultMinValue = DefaultMinValue;
+	type DefaultMaxValue = DefaultMaxValue;
+	type Randomness = TestRandomness;
+	type WeightInfo = ();
 }
 
+/// Accounts endowed with [`ENDOWMENT`] in [`new_test_ext`].
+pub const ENDOWED: [u64; 5] = [1, 2, 3, 7, 8];
+pub const ENDOWMENT: u64 = 100;
+
 // Build genesis storage according to the mock runtime.
 pub fn new_test_ext() -> sp_io::TestExternalities {
-	system::GenesisConfig::default().build_storage::<Test>().unwrap().into()
+	let mut t = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: ENDOWED.iter().map(|who| (*who, ENDOWMENT)).collect(),
+	}.assimilate_storage(&mut t).unwrap();
+	pallet_template::GenesisConfig::<Test>::default().assimilate_storage(&mut t).unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	// Events are not deposited in the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
 }
@@ -0,0 +1,222 @@
+//! Off-chain worker support for the template pallet.
+//!
+//! On every block the off-chain worker fetches a number from an HTTP endpoint and submits it
+//! back on-chain, at most once per `GracePeriod` blocks. The endpoint returns the number as a
+//! plain decimal string in its body.
+//!
+//! Node operators configure the worker through the persistent off-chain storage, e.g. with the
+//! `offchain_localStorageSet` RPC:
+//!
+//! - [`ENDPOINT_KEY`] holds the URL to query, [`DEFAULT_ENDPOINT`] is used if it is not set.
+//! - [`SUBMISSION_KEY`] selects how the value is submitted: `signed` sends a signed
+//!   `do_something` transaction (paying fees), anything else sends an unsigned
+//!   `submit_value_unsigned` transaction with a signed payload.
+//!
+//! Either way the worker needs a [`KEY_TYPE`] key in the node's keystore, which can be added
+//! with the `author_insertKey` RPC. Unsigned submissions are only accepted from keys whose account
+//! is one of the pallet's `Feeders`.
+
+use codec::{Encode, Decode};
+use frame_support::{debug, RuntimeDebug};
+use frame_system::offchain::{
+	SendSignedTransaction, SendUnsignedTransaction, SignedPayload, Signer, SigningTypes,
+};
+use sp_core::crypto::KeyTypeId;
+use sp_runtime::offchain::{
+	http, Duration,
+	storage::StorageValueRef,
+};
+use sp_std::prelude::*;
+
+use crate::{Call, Config, Pallet};
+
+/// Defines application identifier for crypto keys of this module.
+///
+/// Every module that deals with signatures needs to declare its unique identifier for
+/// its crypto keys.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"tmpl");
+
+/// The endpoint queried when none is configured under [`ENDPOINT_KEY`].
+pub const DEFAULT_ENDPOINT: &[u8] = b"http://localhost:8000/value";
+
+/// Persistent off-chain storage key holding the URL the off-chain worker queries.
+pub const ENDPOINT_KEY: &[u8] = b"template::endpoint";
+
+/// Persistent off-chain storage key selecting the submission mode.
+pub const SUBMISSION_KEY: &[u8] = b"template::submission";
+
+/// Persistent off-chain storage key recording the block of the last submission.
+const LAST_SEND_KEY: &[u8] = b"template::last-send";
+
+/// How long to wait for the HTTP endpoint to respond.
+const FETCH_TIMEOUT_MS: u64 = 2_000;
+
+/// Based on the above `KeyTypeId` we need to generate a pallet-specific crypto type wrapper.
+/// We can utilize the supported crypto kinds (`sr25519`, `ed25519` and `ecdsa`) and augment
+/// them with the pallet-specific identifier.
+pub mod crypto {
+	use super::KEY_TYPE;
+	use sp_runtime::{
+		app_crypto::{app_crypto, sr25519},
+		MultiSignature, MultiSigner,
+	};
+	app_crypto!(sr25519, KEY_TYPE);
+
+	/// The off-chain worker's signing key, for runtimes using `MultiSignature`.
+	pub struct TemplateAuthId;
+
+	impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for TemplateAuthId {
+		type RuntimeAppPublic = Public;
+		type GenericSignature = sp_core::sr25519::Signature;
+		type GenericPublic = sp_core::sr25519::Public;
+	}
+}
+
+/// The payload of an unsigned submission, signed by one of the off-chain worker's keys.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct ValuePayload<Public, BlockNumber> {
+	/// The block at which the value was fetched.
+	pub block_number: BlockNumber,
+	/// The fetched value.
+	pub value: u32,
+	/// The key that signed this payload; the value is stored for its account.
+	pub public: Public,
+}
+
+impl<T: SigningTypes> SignedPayload<T> for ValuePayload<T::Public, T::BlockNumber> {
+	fn public(&self) -> T::Public {
+		self.public.clone()
+	}
+}
+
+/// How the off-chain worker submits fetched values.
+#[derive(PartialEq, Eq, RuntimeDebug)]
+enum Submission {
+	Signed,
+	UnsignedWithSignedPayload,
+}
+
+impl<T: Config> Pallet<T> {
+	pub(crate) fn run_offchain_worker(block_number: T::BlockNumber) {
+		if !Self::grace_period_elapsed(block_number) {
+			return;
+		}
+
+		let result = match Self::submission() {
+			Submission::Signed => Self::fetch_value_and_send_signed(),
+			Submission::UnsignedWithSignedPayload =>
+				Self::fetch_value_and_send_unsigned(block_number),
+		};
+		if let Err(e) = result {
+			debug::error!("Template off-chain worker error: {}", e);
+		}
+	}
+
+	/// Check and record in the off-chain storage whether `GracePeriod` blocks have passed since
+	/// the last submission of this node.
+	fn grace_period_elapsed(block_number: T::BlockNumber) -> bool {
+		const RECENTLY_SENT: () = ();
+
+		let last_send = StorageValueRef::persistent(LAST_SEND_KEY);
+		// `mutate` is atomic, so concurrently running workers cannot both pass this check.
+		let res = last_send.mutate(|last: Option<Option<T::BlockNumber>>| {
+			match last {
+				Some(Some(block)) if block_number < block + T::GracePeriod::get() =>
+					Err(RECENTLY_SENT),
+				_ => Ok(block_number),
+			}
+		});
+
+		matches!(res, Ok(Ok(_)))
+	}
+
+	fn submission() -> Submission {
+		match Self::local_storage(SUBMISSION_KEY).as_deref() {
+			Some(b"signed") => Submission::Signed,
+			_ => Submission::UnsignedWithSignedPayload,
+		}
+	}
+
+	/// The URL queried by the off-chain worker.
+	pub fn endpoint() -> Vec<u8> {
+		Self::local_storage(ENDPOINT_KEY).unwrap_or_else(|| DEFAULT_ENDPOINT.to_vec())
+	}
+
+	/// Read a raw value from the persistent off-chain storage.
+	///
+	/// Values set with `offchain_localStorageSet` are raw bytes rather than SCALE encoded, so
+	/// they are read without going through `StorageValueRef`.
+	fn local_storage(key: &[u8]) -> Option<Vec<u8>> {
+		sp_io::offchain::local_storage_get(sp_core::offchain::StorageKind::PERSISTENT, key)
+	}
+
+	/// Fetch the current value from the configured endpoint.
+	pub fn fetch_value() -> Result<u32, http::Error> {
+		let endpoint = Self::endpoint();
+		let url = sp_std::str::from_utf8(&endpoint).map_err(|_| http::Error::Unknown)?;
+
+		let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(FETCH_TIMEOUT_MS));
+		let pending = http::Request::get(url)
+			.deadline(deadline)
+			.send()
+			.map_err(|_| http::Error::IoError)?;
+		let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+		if response.code != 200 {
+			debug::warn!("Unexpected status code: {}", response.code);
+			return Err(http::Error::Unknown);
+		}
+
+		let body = response.body().collect::<Vec<u8>>();
+		sp_std::str::from_utf8(&body)
+			.ok()
+			.and_then(|body| body.trim().parse().ok())
+			.ok_or_else(|| {
+				debug::warn!("Response is not a number: {:?}", body);
+				http::Error::Unknown
+			})
+	}
+
+	fn fetch_value_and_send_signed() -> Result<(), &'static str> {
+		let signer = Signer::<T, T::AuthorityId>::all_accounts();
+		if !signer.can_sign() {
+			return Err(
+				"No local accounts available. Consider adding one via `author_insertKey` RPC."
+			);
+		}
+
+		let value = Self::fetch_value().map_err(|_| "Failed to fetch value")?;
+
+		let results = signer.send_signed_transaction(|_account| Call::do_something(value));
+		for (acc, res) in &results {
+			match res {
+				Ok(()) => debug::info!("[{:?}] Submitted value: {}", acc.id, value),
+				Err(e) => debug::error!("[{:?}] Failed to submit transaction: {:?}", acc.id, e),
+			}
+		}
+
+		Ok(())
+	}
+
+	fn fetch_value_and_send_unsigned(block_number: T::BlockNumber) -> Result<(), &'static str> {
+		// Don't bother fetching if the submission would be rejected anyway.
+		if Self::next_unsigned_at() > block_number {
+			return Err("Too early to send unsigned transaction");
+		}
+
+		let value = Self::fetch_value().map_err(|_| "Failed to fetch value")?;
+
+		let (_, result) = Signer::<T, T::AuthorityId>::any_account()
+			.send_unsigned_transaction(
+				|account| ValuePayload {
+					block_number,
+					value,
+					public: account.public.clone(),
+				},
+				|payload, signature| Call::submit_value_unsigned(payload, signature),
+			)
+			.ok_or("No local accounts accounts available.")?;
+		result.map_err(|()| "Unable to submit transaction")?;
+
+		Ok(())
+	}
+}
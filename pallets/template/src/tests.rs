@@ -1,5 +1,41 @@
-use crate::{Error, mock::*};
-use frame_support::{assert_ok, assert_noop};
+use std::sync::Arc;
+use crate::{
+	Error, Event as TemplateEvent, Expiries, QueuedSweep, Stats, ValuePayload, ValueInfo, Releases,
+	Something, StorageVersion, fuzzing, migrations, mock::*, weights::WeightInfo, DEFAULT_ENDPOINT,
+	ENDPOINT_KEY, SUBMISSION_KEY, VALUE_OUT_OF_BOUNDS,
+};
+use codec::{Encode, Decode};
+use frame_support::{
+	assert_ok, assert_noop,
+	storage::{unhashed, StoragePrefixedMap},
+	traits::{Get, Hooks, ReservableCurrency},
+	weights::RuntimeDbWeight,
+	StorageHasher, Twox64Concat,
+};
+use parking_lot::RwLock;
+use sp_core::offchain::{
+	testing::{self, OffchainState, PoolState},
+	OffchainExt, StorageKind, TransactionPoolExt,
+};
+use sp_runtime::{
+	BuildStorage,
+	DispatchError::BadOrigin,
+	testing::{TestSignature, UintAuthorityId},
+	traits::ValidateUnsigned,
+	transaction_validity::{InvalidTransaction, TransactionSource},
+};
+
+fn last_event() -> Event {
+	System::events().pop().expect("an event was deposited").event
+}
+
+/// Initialize the blocks up to `n`.
+fn run_to_block(n: u64) {
+	while System::block_number() < n {
+		System::set_block_number(System::block_number() + 1);
+		<TemplateModule as Hooks<u64>>::on_initialize(System::block_number());
+	}
+}
 
 #[test]
 fn it_works_for_default_value() {
@@ -7,7 +43,60 @@ fn it_works_for_default_value() {
 		// Dispatch a signed extrinsic.
 		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
 		// Read pallet storage and assert an expected result.
-		assert_eq!(TemplateModule::something(), Some(42));
+		assert_eq!(TemplateModule::value_of(&1), Some(42));
+		assert_eq!(last_event(), Event::pallet_template(TemplateEvent::SomethingStored(1, None, 42)));
+	});
+}
+
+#[test]
+fn values_are_kept_per_account() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(Origin::signed(2), 7));
+		assert_eq!(TemplateModule::value_of(&1), Some(42));
+		assert_eq!(TemplateModule::value_of(&2), Some(7));
+
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 43));
+		assert_eq!(last_event(), Event::pallet_template(TemplateEvent::SomethingStored(1, Some(42), 43)));
+		assert_eq!(TemplateModule::value_of(&2), Some(7));
+	});
+}
+
+#[test]
+fn clear_something_removes_only_the_signers_value() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(Origin::signed(2), 7));
+
+		assert_ok!(TemplateModule::clear_something(Origin::signed(1)));
+		assert_eq!(last_event(), Event::pallet_template(TemplateEvent::SomethingCleared(1, 42)));
+		assert_eq!(TemplateModule::value_of(&1), None);
+		assert_eq!(TemplateModule::value_of(&2), Some(7));
+
+		assert_noop!(
+			TemplateModule::clear_something(Origin::signed(1)),
+			Error::<Test>::NoneValue
+		);
+	});
+}
+
+#[test]
+fn increment_works() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::increment(Origin::signed(1), 5),
+			Error::<Test>::NoneValue
+		);
+
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::increment(Origin::signed(1), 5));
+		assert_eq!(TemplateModule::value_of(&1), Some(47));
+		assert_eq!(last_event(), Event::pallet_template(TemplateEvent::SomethingIncremented(1, 42, 47)));
+
+		assert_noop!(
+			TemplateModule::increment(Origin::signed(1), u32::max_value()),
+			Error::<Test>::StorageOverflow
+		);
 	});
 }
 
@@ -21,3 +110,735 @@ fn correct_error_for_none_value() {
 		);
 	});
 }
+
+#[test]
+fn cause_error_overflows_at_max_value() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), u32::max_value() - 1));
+		assert_ok!(TemplateModule::cause_error(Origin::signed(1)));
+		assert_eq!(TemplateModule::value_of(&1), Some(u32::max_value()));
+
+		assert_noop!(
+			TemplateModule::cause_error(Origin::signed(1)),
+			Error::<Test>::StorageOverflow
+		);
+	});
+}
+
+#[test]
+fn stats_cover_all_accounts() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::stats(), Default::default());
+
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::do_something(Origin::signed(2), u32::max_value()));
+		assert_ok!(TemplateModule::do_something(Origin::signed(3), 7));
+
+		assert_eq!(TemplateModule::stats(), Stats {
+			entries: 3,
+			total: 49 + u32::max_value() as u64,
+			highest: Some(u32::max_value()),
+		});
+	});
+}
+
+#[test]
+fn genesis_config_seeds_values() {
+	let storage = GenesisConfig {
+		frame_system: Some(Default::default()),
+		pallet_balances: Some(pallet_balances::GenesisConfig { balances: vec![(1, 100), (2, 100)] }),
+		pallet_template: Some(crate::GenesisConfig { something: vec![(1, 42), (2, 7)] }),
+	}.build_storage().unwrap();
+
+	sp_io::TestExternalities::from(storage).execute_with(|| {
+		assert_eq!(TemplateModule::value_of(&1), Some(42));
+		assert_eq!(TemplateModule::value_of(&2), Some(7));
+		assert_eq!(TemplateModule::value_of(&3), None);
+		assert_eq!(TemplateModule::deposit_of(&1), Some(DepositPerItem::get()));
+		assert_eq!(Balances::reserved_balance(2), DepositPerItem::get());
+	});
+}
+
+#[test]
+fn storing_a_value_reserves_a_deposit_once() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_eq!(Balances::reserved_balance(1), DepositPerItem::get());
+		assert_eq!(TemplateModule::deposit_of(&1), Some(DepositPerItem::get()));
+
+		// Overwriting or incrementing the value does not reserve again.
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 43));
+		assert_ok!(TemplateModule::increment(Origin::signed(1), 1));
+		assert_eq!(Balances::reserved_balance(1), DepositPerItem::get());
+		assert_eq!(Balances::free_balance(1), ENDOWMENT - DepositPerItem::get());
+	});
+}
+
+#[test]
+fn clearing_a_value_returns_the_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::clear_something(Origin::signed(1)));
+
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::free_balance(1), ENDOWMENT);
+		assert_eq!(TemplateModule::deposit_of(&1), None);
+	});
+}
+
+#[test]
+fn storing_a_value_requires_the_deposit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			TemplateModule::do_something(Origin::signed(4), 42),
+			pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance,
+		);
+		assert_eq!(TemplateModule::value_of(&4), None);
+	});
+}
+
+#[test]
+fn values_without_a_deposit_can_be_cleared() {
+	new_test_ext().execute_with(|| {
+		// Values migrated from before storage deposits were introduced hold nothing.
+		Something::<Test>::insert(1, ValueInfo { value: 42, updated_at: 0 });
+		assert_ok!(TemplateModule::clear_something(Origin::signed(1)));
+		assert_eq!(Balances::free_balance(1), ENDOWMENT);
+	});
+}
+
+fn offchain_test_ext() -> (sp_io::TestExternalities, Arc<RwLock<OffchainState>>, Arc<RwLock<PoolState>>) {
+	let (offchain, offchain_state) = testing::TestOffchainExt::new();
+	let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+
+	let mut t = new_test_ext();
+	t.register_extension(OffchainExt::new(offchain));
+	t.register_extension(TransactionPoolExt::new(pool));
+	(t, offchain_state, pool_state)
+}
+
+fn expect_value_request(state: &mut OffchainState, uri: &str, body: &[u8]) {
+	state.expect_request(testing::PendingRequest {
+		method: "GET".into(),
+		uri: uri.into(),
+		response: Some(body.to_vec()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+fn default_endpoint() -> &'static str {
+	std::str::from_utf8(DEFAULT_ENDPOINT).unwrap()
+}
+
+fn signed_payload(public: u64, block_number: u64, value: u32) -> (ValuePayload<UintAuthorityId, u64>, TestSignature) {
+	let payload = ValuePayload { block_number, value, public: UintAuthorityId(public) };
+	let signature = TestSignature(public, payload.encode());
+	(payload, signature)
+}
+
+#[test]
+fn fetch_value_parses_the_response_body() {
+	let (mut t, offchain_state, _) = offchain_test_ext();
+	expect_value_request(&mut offchain_state.write(), default_endpoint(), b"1234\n");
+
+	t.execute_with(|| {
+		assert_eq!(TemplateModule::fetch_value(), Ok(1234));
+	});
+}
+
+#[test]
+fn fetch_value_uses_the_configured_endpoint() {
+	let (mut t, offchain_state, _) = offchain_test_ext();
+	expect_value_request(&mut offchain_state.write(), "http://example.com/n", b"7");
+
+	t.execute_with(|| {
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, ENDPOINT_KEY, b"http://example.com/n");
+		assert_eq!(TemplateModule::fetch_value(), Ok(7));
+	});
+}
+
+#[test]
+fn fetch_value_rejects_non_numeric_bodies() {
+	let (mut t, offchain_state, _) = offchain_test_ext();
+	expect_value_request(&mut offchain_state.write(), default_endpoint(), b"forty-two");
+
+	t.execute_with(|| {
+		assert!(TemplateModule::fetch_value().is_err());
+	});
+}
+
+#[test]
+fn offchain_worker_submits_unsigned_with_signed_payload() {
+	let (mut t, offchain_state, pool_state) = offchain_test_ext();
+	expect_value_request(&mut offchain_state.write(), default_endpoint(), b"1234");
+	UintAuthorityId::set_all_keys(vec![7]);
+
+	t.execute_with(|| {
+		<TemplateModule as Hooks<u64>>::offchain_worker(1);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		assert!(pool_state.read().transactions.is_empty());
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, None);
+
+		let (payload, signature) = signed_payload(7, 1, 1234);
+		assert_eq!(tx.call, Call::TemplateModule(crate::Call::submit_value_unsigned(payload, signature)));
+	});
+}
+
+#[test]
+fn offchain_worker_submits_signed_when_configured() {
+	let (mut t, offchain_state, pool_state) = offchain_test_ext();
+	expect_value_request(&mut offchain_state.write(), default_endpoint(), b"1234");
+	UintAuthorityId::set_all_keys(vec![7]);
+
+	t.execute_with(|| {
+		sp_io::offchain::local_storage_set(StorageKind::PERSISTENT, SUBMISSION_KEY, b"signed");
+		<TemplateModule as Hooks<u64>>::offchain_worker(1);
+
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let tx = Extrinsic::decode(&mut &*tx).unwrap();
+		assert_eq!(tx.signature, Some((7, ())));
+		assert_eq!(tx.call, Call::TemplateModule(crate::Call::do_something(1234)));
+	});
+}
+
+#[test]
+fn offchain_worker_respects_the_grace_period() {
+	let (mut t, offchain_state, pool_state) = offchain_test_ext();
+	expect_value_request(&mut offchain_state.write(), default_endpoint(), b"1234");
+	UintAuthorityId::set_all_keys(vec![7]);
+
+	t.execute_with(|| {
+		<TemplateModule as Hooks<u64>>::offchain_worker(1);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+
+		// No request is expected, so fetching again would panic.
+		<TemplateModule as Hooks<u64>>::offchain_worker(1 + GracePeriod::get() - 1);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+	});
+}
+
+#[test]
+fn submit_value_unsigned_stores_the_value_for_the_signer() {
+	new_test_ext().execute_with(|| {
+		let (payload, signature) = signed_payload(7, 1, 1234);
+		assert_ok!(TemplateModule::submit_value_unsigned(Origin::none(), payload, signature));
+
+		assert_eq!(TemplateModule::value_of(&7), Some(1234));
+		assert_eq!(TemplateModule::next_unsigned_at(), 1 + UnsignedInterval::get());
+		assert_eq!(last_event(), Event::pallet_template(TemplateEvent::SomethingStored(7, None, 1234)));
+	});
+}
+
+#[test]
+fn validate_unsigned_checks_the_signature() {
+	new_test_ext().execute_with(|| {
+		let (payload, signature) = signed_payload(7, 1, 1234);
+		let call = crate::Call::submit_value_unsigned(payload.clone(), signature);
+		assert!(TemplateModule::validate_unsigned(TransactionSource::External, &call).is_ok());
+
+		// Signed by someone else.
+		let (_, signature) = signed_payload(8, 1, 1234);
+		let call = crate::Call::submit_value_unsigned(payload, signature);
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadProof.into(),
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_enforces_the_interval() {
+	new_test_ext().execute_with(|| {
+		let (payload, signature) = signed_payload(7, 1, 1234);
+		assert_ok!(TemplateModule::submit_value_unsigned(Origin::none(), payload, signature));
+
+		// Too early.
+		System::set_block_number(UnsignedInterval::get());
+		let (payload, signature) = signed_payload(7, UnsignedInterval::get(), 1);
+		let call = crate::Call::submit_value_unsigned(payload, signature);
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Stale.into(),
+		);
+
+		// Fetched in a future block.
+		let (payload, signature) = signed_payload(7, 1 + UnsignedInterval::get(), 1);
+		let call = crate::Call::submit_value_unsigned(payload, signature);
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Future.into(),
+		);
+
+		System::set_block_number(1 + UnsignedInterval::get());
+		assert!(TemplateModule::validate_unsigned(TransactionSource::External, &call).is_ok());
+	});
+}
+
+#[test]
+fn validate_unsigned_only_accepts_feeders() {
+	new_test_ext().execute_with(|| {
+		assert!(!FEEDERS.contains(&9));
+		let (payload, signature) = signed_payload(9, 1, 1234);
+		let call = crate::Call::submit_value_unsigned(payload, signature);
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadSigner.into(),
+		);
+	});
+}
+
+#[test]
+fn validate_unsigned_rejects_values_that_cannot_be_stored() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_bounds(Origin::root(), 0, 100));
+		let (payload, signature) = signed_payload(7, 1, 1234);
+		let call = crate::Call::submit_value_unsigned(payload, signature);
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Custom(VALUE_OUT_OF_BOUNDS).into(),
+		);
+
+		// The signer cannot afford the deposit of a new value...
+		assert_ok!(Balances::reserve(&7, ENDOWMENT - DepositPerItem::get() + 1));
+		let (payload, signature) = signed_payload(7, 1, 42);
+		let call = crate::Call::submit_value_unsigned(payload, signature);
+		assert_eq!(
+			TemplateModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Payment.into(),
+		);
+
+		// ...but needs none to update its value.
+		Balances::unreserve(&7, ENDOWMENT);
+		assert_ok!(TemplateModule::do_something(Origin::signed(7), 1));
+		assert_ok!(Balances::reserve(&7, Balances::free_balance(&7) - 1));
+		assert!(TemplateModule::validate_unsigned(TransactionSource::External, &call).is_ok());
+	});
+}
+
+#[test]
+fn failing_unsigned_submissions_still_use_up_the_interval() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_bounds(Origin::root(), 0, 100));
+		let (payload, signature) = signed_payload(7, 1, 1234);
+		assert_eq!(
+			TemplateModule::submit_value_unsigned(Origin::none(), payload, signature)
+				.map_err(|e| e.error),
+			Err(Error::<Test>::ValueTooHigh.into()),
+		);
+		assert_eq!(TemplateModule::value_of(&7), None);
+		assert_eq!(TemplateModule::next_unsigned_at(), 1 + UnsignedInterval::get());
+	});
+}
+
+#[test]
+fn values_record_the_block_they_were_written_in() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_eq!(TemplateModule::something(1), Some(ValueInfo { value: 42, updated_at: 1 }));
+
+		System::set_block_number(3);
+		assert_ok!(TemplateModule::increment(Origin::signed(1), 1));
+		assert_eq!(TemplateModule::something(1), Some(ValueInfo { value: 43, updated_at: 3 }));
+	});
+}
+
+#[test]
+fn new_chains_start_at_the_latest_storage_version() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V3);
+		assert_eq!(
+			<TemplateModule as Hooks<u64>>::on_runtime_upgrade(),
+			<Test as frame_system::Config>::DbWeight::get().reads(2),
+		);
+	});
+}
+
+#[test]
+fn migration_to_v2_translates_old_values() {
+	new_test_ext().execute_with(|| {
+		// Seed the V1 layout: bare `u32`s per account, plus the global value of the original
+		// template pallet at the map's prefix.
+		StorageVersion::<Test>::put(Releases::V1);
+		unhashed::put(&Something::<Test>::hashed_key_for(1), &42u32);
+		unhashed::put(&Something::<Test>::hashed_key_for(2), &7u32);
+		unhashed::put(&Something::<Test>::final_prefix(), &3u32);
+
+		System::set_block_number(5);
+		assert_ok!(migrations::v2::pre_migrate::<Test>());
+		assert_eq!(
+			migrations::v2::migrate::<Test>(),
+			<Test as frame_system::Config>::DbWeight::get().reads_writes(4, 4),
+		);
+		assert_ok!(migrations::v2::post_migrate::<Test>());
+
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V2);
+		assert_eq!(TemplateModule::something(1), Some(ValueInfo { value: 42, updated_at: 5 }));
+		assert_eq!(TemplateModule::something(2), Some(ValueInfo { value: 7, updated_at: 5 }));
+		assert_eq!(unhashed::get_raw(&Something::<Test>::final_prefix()), None);
+
+		// Running it again is a no-op.
+		assert_eq!(
+			migrations::v2::migrate::<Test>(),
+			<Test as frame_system::Config>::DbWeight::get().reads(1),
+		);
+		assert_eq!(TemplateModule::something(1), Some(ValueInfo { value: 42, updated_at: 5 }));
+	});
+}
+
+#[test]
+fn migration_to_v3_queues_every_value_once() {
+	new_test_ext().execute_with(|| {
+		// Seed the V2 layout: a list of accounts per sweep block, with stale and missing entries.
+		StorageVersion::<Test>::put(Releases::V2);
+		Something::<Test>::insert(1, ValueInfo { value: 42, updated_at: 20 });
+		Something::<Test>::insert(2, ValueInfo { value: 7, updated_at: 1 });
+		let mut old_key = Expiries::<Test>::final_prefix().to_vec();
+		old_key.extend(Twox64Concat::hash(&25u64.encode()));
+		unhashed::put(&old_key, &vec![1u64, 2, 3]);
+
+		System::set_block_number(27);
+		assert_ok!(migrations::v3::pre_migrate::<Test>());
+		assert_eq!(
+			<TemplateModule as Hooks<u64>>::on_runtime_upgrade(),
+			<Test as frame_system::Config>::DbWeight::get().reads(1) +
+				<Test as frame_system::Config>::DbWeight::get().reads_writes(5, 8),
+		);
+		assert_ok!(migrations::v3::post_migrate::<Test>());
+
+		assert_eq!(StorageVersion::<Test>::get(), Releases::V3);
+		assert_eq!(unhashed::get_raw(&old_key), None);
+		assert_eq!(TemplateModule::expiries(40), vec![1]);
+		// The overdue value expires in the next sweep.
+		assert_eq!(TemplateModule::expiries(30), vec![2]);
+		assert_eq!(TemplateModule::queued_sweep(2), Some(30));
+		assert_eq!(TemplateModule::next_sweep(), 30);
+
+		assert_eq!(
+			migrations::v3::migrate::<Test>(),
+			<Test as frame_system::Config>::DbWeight::get().reads(1),
+		);
+		assert!(migrations::v3::pre_migrate::<Test>().is_err());
+	});
+}
+
+#[test]
+fn updating_an_overdue_migrated_value_keeps_it_past_the_sweep() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::<Test>::put(Releases::V2);
+		Something::<Test>::insert(2, ValueInfo { value: 7, updated_at: 1 });
+		System::set_block_number(27);
+		<TemplateModule as Hooks<u64>>::on_runtime_upgrade();
+		assert_eq!(TemplateModule::expiries(30), vec![2]);
+
+		// The update replaces the entry the migration queued, not the one its last update would.
+		run_to_block(28);
+		assert_ok!(TemplateModule::do_something(Origin::signed(2), 8));
+		assert!(TemplateModule::expiries(30).is_empty());
+		assert_eq!(TemplateModule::expiries(50), vec![2]);
+
+		run_to_block(30);
+		assert_eq!(TemplateModule::value_of(&2), Some(8));
+		assert_eq!(TemplateModule::queued_sweep(2), Some(50));
+	});
+}
+
+#[test]
+fn values_queued_before_a_longer_ttl_are_queued_again() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		// Queue the value earlier than its last update expires, as a longer `Ttl` would.
+		Expiries::<Test>::remove(25, 1);
+		Expiries::<Test>::insert(20, 1, ());
+		QueuedSweep::<Test>::insert(1, 20);
+
+		run_to_block(20);
+		assert_eq!(TemplateModule::value_of(&1), Some(42));
+		assert_eq!(TemplateModule::expiries(25), vec![1]);
+
+		run_to_block(25);
+		assert_eq!(TemplateModule::value_of(&1), None);
+	});
+}
+
+#[test]
+fn migration_checks_reject_unexpected_layouts() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		// Already migrated.
+		assert!(migrations::v2::pre_migrate::<Test>().is_err());
+		assert!(migrations::v3::pre_migrate::<Test>().is_err());
+
+		StorageVersion::<Test>::put(Releases::V1);
+		// A V2 value does not decode as a bare `u32`.
+		assert!(migrations::v2::pre_migrate::<Test>().is_err());
+	});
+}
+
+#[test]
+fn expiry_rounds_up_to_the_next_sweep() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::expires_at(0), Some(20));
+		assert_eq!(TemplateModule::expires_at(1), Some(25));
+		assert_eq!(TemplateModule::expires_at(5), Some(25));
+		assert_eq!(TemplateModule::expires_at(6), Some(30));
+	});
+}
+
+#[test]
+fn values_expire_after_their_ttl() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_eq!(TemplateModule::expiries(25), vec![1]);
+
+		run_to_block(24);
+		assert_eq!(TemplateModule::value_of(&1), Some(42));
+
+		run_to_block(25);
+		assert_eq!(TemplateModule::value_of(&1), None);
+		assert_eq!(TemplateModule::deposit_of(&1), None);
+		assert_eq!(Balances::free_balance(1), ENDOWMENT);
+		assert!(TemplateModule::expiries(25).is_empty());
+		assert_eq!(last_event(), Event::pallet_template(TemplateEvent::SomethingExpired(1, 42)));
+	});
+}
+
+#[test]
+fn updating_a_value_postpones_its_expiry() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		// Updates within the same sweep window keep the entry where it is.
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 43));
+		assert_eq!(TemplateModule::expiries(25), vec![1]);
+
+		run_to_block(10);
+		assert_ok!(TemplateModule::increment(Origin::signed(1), 1));
+		assert!(TemplateModule::expiries(25).is_empty());
+		assert_eq!(TemplateModule::expiries(30), vec![1]);
+
+		run_to_block(25);
+		assert_eq!(TemplateModule::value_of(&1), Some(44));
+
+		run_to_block(30);
+		assert_eq!(TemplateModule::value_of(&1), None);
+	});
+}
+
+#[test]
+fn clearing_a_value_removes_its_expiry() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		assert_ok!(TemplateModule::clear_something(Origin::signed(1)));
+		assert!(TemplateModule::expiries(25).is_empty());
+
+		run_to_block(25);
+		assert_eq!(last_event(), Event::pallet_template(TemplateEvent::SomethingCleared(1, 42)));
+	});
+}
+
+#[test]
+fn sweeps_are_bounded_by_max_expiries_per_block() {
+	new_test_ext().execute_with(|| {
+		for who in 1..=3 {
+			assert_ok!(TemplateModule::do_something(Origin::signed(who), who as u32));
+		}
+		run_to_block(24);
+
+		System::set_block_number(25);
+		let db: RuntimeDbWeight = <Test as frame_system::Config>::DbWeight::get();
+		assert_eq!(
+			<TemplateModule as Hooks<u64>>::on_initialize(25),
+			<() as WeightInfo>::expire(2) + db.reads(1),
+		);
+		let left: Vec<u64> = (1..=3).filter(|who| TemplateModule::value_of(who).is_some()).collect();
+		assert_eq!(left.len(), 1);
+		assert_eq!(TemplateModule::expiries(25), left);
+		assert_eq!(TemplateModule::next_sweep(), 25);
+
+		// The leftover expires in the next block, which then moves on to the next sweep.
+		System::set_block_number(26);
+		assert_eq!(
+			<TemplateModule as Hooks<u64>>::on_initialize(26),
+			<() as WeightInfo>::expire(1) + db.reads(1),
+		);
+		assert_eq!(TemplateModule::value_of(&left[0]), None);
+		assert_eq!(TemplateModule::next_sweep(), 30);
+	});
+}
+
+#[test]
+fn blocks_without_due_values_only_read_the_next_sweep() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 42));
+		run_to_block(21);
+
+		let db: RuntimeDbWeight = <Test as frame_system::Config>::DbWeight::get();
+		assert_eq!(<TemplateModule as Hooks<u64>>::on_initialize(24), db.reads(1));
+		assert!(<TemplateModule as Hooks<u64>>::on_initialize(25) > db.reads(1));
+	});
+}
+
+#[test]
+fn catching_up_on_empty_sweeps_is_bounded() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(100);
+		let db: RuntimeDbWeight = <Test as frame_system::Config>::DbWeight::get();
+		assert_eq!(
+			<TemplateModule as Hooks<u64>>::on_initialize(100),
+			<() as WeightInfo>::expire(0) + db.reads(MaxExpiriesPerBlock::get().into()),
+		);
+		assert_eq!(TemplateModule::next_sweep(), 10);
+	});
+}
+
+#[test]
+fn genesis_values_expire_after_their_ttl() {
+	let storage = GenesisConfig {
+		frame_system: Some(Default::default()),
+		pallet_balances: Some(pallet_balances::GenesisConfig { balances: vec![(1, 100)] }),
+		pallet_template: Some(crate::GenesisConfig { something: vec![(1, 42)] }),
+	}.build_storage().unwrap();
+
+	sp_io::TestExternalities::from(storage).execute_with(|| {
+		assert_eq!(TemplateModule::expiries(Ttl::get()), vec![1]);
+	});
+}
+
+#[test]
+fn integrity_test_passes() {
+	new_test_ext().execute_with(|| {
+		<TemplateModule as Hooks<u64>>::integrity_test();
+	});
+}
+
+#[test]
+fn random_action_sequences_uphold_invariants() {
+	for seed in 0..200 {
+		fuzzing::run(&fuzzing::random_actions(seed, 64));
+	}
+}
+
+#[test]
+fn action_sequences_cross_the_overflow_boundary() {
+	use fuzzing::Action::*;
+
+	fuzzing::run(&[
+		DoSomething(1, u32::max_value() - 2),
+		CauseError(1),
+		Increment(1, 1),
+		CauseError(1),
+		Increment(1, 0),
+		ClearSomething(1),
+		CauseError(1),
+		DoSomething(4, 1),
+		DoSomething(2, 0),
+		Increment(2, u32::max_value()),
+		CauseError(2),
+		SetBounds(10, 20),
+		DoSomething(3, 9),
+		DoSomething(3, 21),
+		DoSomething(3, 20),
+		CauseError(3),
+		ForceSet(3, 100),
+		ForceSet(4, 5),
+		Increment(4, 5),
+		ClearSomething(4),
+		SetBounds(20, 10),
+	]);
+}
+
+#[test]
+fn actions_decode_from_bytes() {
+	use fuzzing::{actions_from_bytes, Action::*};
+
+	assert_eq!(actions_from_bytes(&[0, 0, 42, 0, 0, 0, 0xFF]), vec![DoSomething(1, 42)]);
+	assert_eq!(actions_from_bytes(&[0b01011, 3, 0, 0, 0, 0]), vec![Increment(4, u32::max_value())]);
+	assert_eq!(
+		actions_from_bytes(&[2, 1, 0, 0, 0, 0, 4, 2, 0, 0, 0, 0]),
+		vec![CauseError(2), ClearSomething(3)],
+	);
+	assert_eq!(
+		actions_from_bytes(&[5, 0, 7, 0, 0, 0, 6, 2, 5, 0, 0, 0, 7, 3, 5, 0, 0, 0]),
+		vec![ForceSet(1, 7), SetBounds(5, 5), SetBounds(5, 4)],
+	);
+	assert!(actions_from_bytes(&[0; 5]).is_empty());
+}
+
+#[test]
+fn bounds_default_to_the_configured_constants() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(TemplateModule::bounds(), (DefaultMinValue::get(), DefaultMaxValue::get()));
+	});
+}
+
+#[test]
+fn set_bounds_requires_the_admin_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(TemplateModule::set_bounds(Origin::signed(1), 1, 10), BadOrigin);
+		assert_noop!(TemplateModule::set_bounds(Origin::root(), 10, 1), Error::<Test>::InvalidBounds);
+
+		assert_ok!(TemplateModule::set_bounds(Origin::root(), 1, 10));
+		assert_eq!(TemplateModule::bounds(), (1, 10));
+		assert_eq!(last_event(), Event::pallet_template(TemplateEvent::BoundsSet(1, 10)));
+	});
+}
+
+#[test]
+fn roll_stores_random_values_within_bounds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_bounds(Origin::root(), 10, 20));
+
+		let mut rolled = Vec::new();
+		for &who in &ENDOWED {
+			assert_ok!(TemplateModule::roll(Origin::signed(who)));
+			let value = TemplateModule::value_of(&who).unwrap();
+			assert!((10..=20).contains(&value));
+			assert_eq!(TemplateModule::deposit_of(&who), Some(DepositPerItem::get()));
+			rolled.push(value);
+		}
+		rolled.dedup();
+		assert!(rolled.len() > 1, "rolls should differ between accounts: {:?}", rolled);
+	});
+}
+
+#[test]
+fn values_must_be_within_bounds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_bounds(Origin::root(), 10, 20));
+
+		assert_noop!(TemplateModule::do_something(Origin::signed(1), 9), Error::<Test>::ValueTooLow);
+		assert_noop!(TemplateModule::do_something(Origin::signed(1), 21), Error::<Test>::ValueTooHigh);
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), 20));
+
+		assert_noop!(TemplateModule::cause_error(Origin::signed(1)), Error::<Test>::ValueTooHigh);
+		assert_noop!(TemplateModule::increment(Origin::signed(1), 5), Error::<Test>::ValueTooHigh);
+	});
+}
+
+#[test]
+fn overflow_is_still_reported_before_the_bounds() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::do_something(Origin::signed(1), u32::max_value()));
+		assert_ok!(TemplateModule::set_bounds(Origin::root(), 0, 10));
+		assert_noop!(TemplateModule::cause_error(Origin::signed(1)), Error::<Test>::StorageOverflow);
+	});
+}
+
+#[test]
+fn force_set_bypasses_bounds_and_deposits() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(TemplateModule::set_bounds(Origin::root(), 10, 20));
+		assert_noop!(TemplateModule::force_set(Origin::signed(1), 4, 100), BadOrigin);
+
+		// Account 4 has no funds.
+		assert_ok!(TemplateModule::force_set(Origin::root(), 4, 100));
+		assert_eq!(TemplateModule::value_of(&4), Some(100));
+		assert_eq!(TemplateModule::deposit_of(&4), None);
+		assert_eq!(last_event(), Event::pallet_template(TemplateEvent::SomethingForced(4, None, 100)));
+
+		assert_ok!(TemplateModule::clear_something(Origin::signed(4)));
+		assert_eq!(TemplateModule::value_of(&4), None);
+	});
+}
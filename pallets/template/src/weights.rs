@@ -0,0 +1,126 @@
+//! Weights for pallet_template
+//!
+//! These values are estimates, not benchmark results. Each call is charged one
+//! `ExtrinsicBaseWeight` for its execution, plus the database reads and writes it makes, priced
+//! by `DbWeight`. `expire` is charged one `ExtrinsicBaseWeight` per expired value instead.
+//! Replace them with the output of
+//!
+//! ```text
+//! ./target/release/node-template benchmark --chain=dev --steps=50 --repeat=20 \
+//!     --pallet=template --extrinsic=* --execution=wasm --wasm-execution=compiled \
+//!     --heap-pages=4096 --output=./pallets/template/src/weights.rs
+//! ```
+//!
+//! on reference hardware before relying on them.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{Weight, constants::{ExtrinsicBaseWeight, RocksDbWeight}},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_template.
+pub trait WeightInfo {
+	fn do_something() -> Weight;
+	fn clear_something() -> Weight;
+	fn increment() -> Weight;
+	fn roll() -> Weight;
+	fn cause_error() -> Weight;
+	fn expire(n: u32, ) -> Weight;
+	fn set_bounds() -> Weight;
+	fn force_set() -> Weight;
+}
+
+/// Weights for pallet_template using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn do_something() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn clear_something() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn increment() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn roll() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn cause_error() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn expire(n: u32, ) -> Weight {
+		ExtrinsicBaseWeight::get().saturating_mul(n as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+	}
+	fn set_bounds() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn force_set() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn do_something() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn clear_something() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(5 as Weight))
+	}
+	fn increment() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn roll() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	fn cause_error() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn expire(n: u32, ) -> Weight {
+		ExtrinsicBaseWeight::get().saturating_mul(n as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((5 as Weight).saturating_mul(n as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((5 as Weight).saturating_mul(n as Weight)))
+	}
+	fn set_bounds() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn force_set() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+}
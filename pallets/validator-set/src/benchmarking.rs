@@ -0,0 +1,45 @@
+//! Benchmarking setup for pallet-validator-set
+
+use super::*;
+
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::traits::EnsureOrigin;
+use sp_std::prelude::*;
+#[allow(unused)]
+use crate::Pallet as ValidatorSet;
+
+const SEED: u32 = 0;
+
+/// A set of `n` validators.
+fn set_validators<T: Config>(n: u32) {
+	let validators = (0..n).map(|i| account("validator", i, SEED)).collect::<Vec<T::AccountId>>();
+	Validators::<T>::put(validators);
+}
+
+benchmarks! {
+	add_validator {
+		set_validators::<T>(T::MinValidators::get().max(16));
+		let origin = T::AddRemoveOrigin::successful_origin();
+		let who: T::AccountId = account("new", 0, SEED);
+	}: _(origin, who.clone())
+	verify {
+		assert!(ValidatorSet::<T>::validators().contains(&who));
+	}
+
+	// The validator is at the end of the set, so the whole of it is searched.
+	remove_validator {
+		let n = T::MinValidators::get().max(16) + 1;
+		set_validators::<T>(n);
+		let origin = T::AddRemoveOrigin::successful_origin();
+		let who: T::AccountId = account("validator", n - 1, SEED);
+	}: _(origin, who.clone())
+	verify {
+		assert!(!ValidatorSet::<T>::validators().contains(&who));
+	}
+}
+
+impl_benchmark_test_suite!(
+	ValidatorSet,
+	crate::mock::new_test_ext(),
+	crate::mock::Test,
+);
@@ -0,0 +1,245 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Validator Set Pallet
+//!
+//! Maintains the set of validators and hands it to `pallet_session` as its `SessionManager`,
+//! so validators can be added and removed on a live chain by `AddRemoveOrigin`.
+//!
+//! A validator needs session keys to author blocks: before it is added it should generate them
+//! with `author_rotateKeys` and register them with `session.set_keys`. Changes to the set are
+//! queued by the session pallet and take effect at the start of the session after next.
+//!
+//! The pallet also handles offences reported against validators, e.g. GRANDPA equivocations: an
+//! offender is disabled for the rest of the session and removed from the set, as long as that
+//! leaves `MinValidators`.
+
+pub use pallet::*;
+
+use frame_support::{traits::Get, weights::Weight};
+use sp_runtime::{traits::Convert, Perbill};
+use sp_staking::{
+	offence::{OffenceDetails, OnOffenceHandler},
+	SessionIndex,
+};
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+pub mod weights;
+pub use weights::WeightInfo;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use frame_support::{dispatch::DispatchResultWithPostInfo, pallet_prelude::*};
+	use frame_system::pallet_prelude::*;
+	use sp_std::prelude::*;
+	use super::WeightInfo;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Because this pallet emits events, it depends on the runtime's definition of an event.
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The origin allowed to add and remove validators.
+		type AddRemoveOrigin: EnsureOrigin<Self::Origin>;
+		/// The number of validators that cannot be removed, so the chain keeps producing blocks.
+		#[pallet::constant]
+		type MinValidators: Get<u32>;
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T>(_);
+
+	/// The current set of validators, handed to the session pallet when it changes.
+	#[pallet::storage]
+	#[pallet::getter(fn validators)]
+	pub type Validators<T: Config> = StorageValue<_, Vec<T::AccountId>, ValueQuery>;
+
+	/// Whether `Validators` changed since it was last handed to the session pallet.
+	#[pallet::storage]
+	pub type Changed<T> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// The validators of the first sessions.
+		pub validators: Vec<T::AccountId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { validators: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			assert!(
+				self.validators.len() >= T::MinValidators::get() as usize,
+				"genesis must have at least `MinValidators` validators",
+			);
+			<Validators<T>>::put(&self.validators);
+			// The session pallet's genesis picks the validators up from `new_session`.
+			<Changed<T>>::put(true);
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(T::AccountId = "AccountId")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A validator was added; it becomes active two sessions from now. [who]
+		ValidatorAdded(T::AccountId),
+		/// A validator was removed; it leaves the set two sessions from now. [who]
+		ValidatorRemoved(T::AccountId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The account is already a validator.
+		AlreadyValidator,
+		/// The account is not a validator.
+		NotValidator,
+		/// Removing the validator would leave fewer than `MinValidators`.
+		TooFewValidators,
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Add `who` to the validator set.
+		///
+		/// The dispatch origin must be `AddRemoveOrigin`.
+		#[pallet::weight(T::WeightInfo::add_validator())]
+		pub fn add_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResultWithPostInfo {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			<Validators<T>>::try_mutate(|validators| -> DispatchResult {
+				ensure!(!validators.contains(&who), Error::<T>::AlreadyValidator);
+				validators.push(who.clone());
+				Ok(())
+			})?;
+			<Changed<T>>::put(true);
+
+			Self::deposit_event(Event::ValidatorAdded(who));
+			Ok(().into())
+		}
+
+		/// Remove `who` from the validator set.
+		///
+		/// The dispatch origin must be `AddRemoveOrigin`.
+		#[pallet::weight(T::WeightInfo::remove_validator())]
+		pub fn remove_validator(origin: OriginFor<T>, who: T::AccountId) -> DispatchResultWithPostInfo {
+			T::AddRemoveOrigin::ensure_origin(origin)?;
+
+			Self::do_remove_validator(who)?;
+			Ok(().into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		pub(crate) fn do_remove_validator(who: T::AccountId) -> DispatchResult {
+			<Validators<T>>::try_mutate(|validators| -> DispatchResult {
+				let index = validators.iter()
+					.position(|v| v == &who)
+					.ok_or(Error::<T>::NotValidator)?;
+				ensure!(
+					validators.len() > T::MinValidators::get() as usize,
+					Error::<T>::TooFewValidators,
+				);
+				validators.remove(index);
+				Ok(())
+			})?;
+			<Changed<T>>::put(true);
+
+			Self::deposit_event(Event::ValidatorRemoved(who));
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> pallet_session::SessionManager<T::AccountId> for Pallet<T> {
+	/// Hand the validators to the session pallet if they changed since the last call.
+	fn new_session(_new_index: SessionIndex) -> Option<Vec<T::AccountId>> {
+		if <Changed<T>>::take() {
+			Some(Self::validators())
+		} else {
+			None
+		}
+	}
+
+	fn end_session(_end_index: SessionIndex) {}
+
+	fn start_session(_start_index: SessionIndex) {}
+}
+
+/// Validators carry no identification beyond their account, so the historical session data only
+/// records `()` for each of them.
+impl<T: Config> pallet_session::historical::SessionManager<T::AccountId, ()> for Pallet<T> {
+	fn new_session(new_index: SessionIndex) -> Option<Vec<(T::AccountId, ())>> {
+		<Self as pallet_session::SessionManager<_>>::new_session(new_index)
+			.map(|validators| validators.into_iter().map(|v| (v, ())).collect())
+	}
+
+	fn end_session(end_index: SessionIndex) {
+		<Self as pallet_session::SessionManager<_>>::end_session(end_index)
+	}
+
+	fn start_session(start_index: SessionIndex) {
+		<Self as pallet_session::SessionManager<_>>::start_session(start_index)
+	}
+}
+
+/// The `FullIdentificationOf` for `pallet_session::historical`, matching the `()` identification
+/// recorded by this pallet.
+pub struct FullIdentificationOf<T>(PhantomData<T>);
+
+impl<T: Config> Convert<T::AccountId, Option<()>> for FullIdentificationOf<T> {
+	fn convert(_validator: T::AccountId) -> Option<()> {
+		Some(())
+	}
+}
+
+impl<T> OnOffenceHandler<T::AccountId, pallet_session::historical::IdentificationTuple<T>, Weight>
+	for Pallet<T>
+where
+	T: Config + pallet_session::historical::Config<
+		ValidatorId = <T as frame_system::Config>::AccountId,
+		FullIdentification = (),
+	>,
+{
+	/// Disable the offenders for the rest of the session and remove them from the set.
+	///
+	/// There is no stake to slash, so `slash_fraction` is ignored.
+	fn on_offence(
+		offenders: &[OffenceDetails<T::AccountId, pallet_session::historical::IdentificationTuple<T>>],
+		_slash_fraction: &[Perbill],
+		_session: SessionIndex,
+	) -> Result<Weight, ()> {
+		for details in offenders {
+			let (who, ()) = details.offender.clone();
+			let _ = <pallet_session::Module<T>>::disable(&who);
+			// Keep the chain alive rather than removing the last validators.
+			let _ = Self::do_remove_validator(who);
+		}
+
+		let per_offender = T::DbWeight::get().reads_writes(3, 4);
+		Ok(per_offender.saturating_mul(offenders.len() as Weight))
+	}
+
+	fn can_report() -> bool {
+		true
+	}
+}
@@ -0,0 +1,67 @@
+use crate::{Error, Event as ValidatorSetEvent, mock::*};
+use frame_support::{assert_ok, assert_noop};
+use pallet_session::SessionManager;
+use sp_runtime::DispatchError::BadOrigin;
+
+fn last_event() -> Event {
+	System::events().pop().expect("an event was deposited").event
+}
+
+#[test]
+fn genesis_validators_start_the_first_session() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(ValidatorSet::validators(), vec![1, 2, 3]);
+		assert_eq!(ValidatorSet::new_session(0), Some(vec![1, 2, 3]));
+		// Unchanged from then on.
+		assert_eq!(ValidatorSet::new_session(1), None);
+	});
+}
+
+#[test]
+fn add_validator_works() {
+	new_test_ext().execute_with(|| {
+		ValidatorSet::new_session(0);
+
+		assert_ok!(ValidatorSet::add_validator(Origin::root(), 4));
+		assert_eq!(ValidatorSet::validators(), vec![1, 2, 3, 4]);
+		assert_eq!(last_event(), Event::pallet_validator_set(ValidatorSetEvent::ValidatorAdded(4)));
+
+		assert_eq!(ValidatorSet::new_session(2), Some(vec![1, 2, 3, 4]));
+		assert_eq!(ValidatorSet::new_session(3), None);
+	});
+}
+
+#[test]
+fn remove_validator_works() {
+	new_test_ext().execute_with(|| {
+		ValidatorSet::new_session(0);
+
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 2));
+		assert_eq!(ValidatorSet::validators(), vec![1, 3]);
+		assert_eq!(last_event(), Event::pallet_validator_set(ValidatorSetEvent::ValidatorRemoved(2)));
+
+		assert_eq!(ValidatorSet::new_session(2), Some(vec![1, 3]));
+	});
+}
+
+#[test]
+fn changes_require_the_add_remove_origin() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ValidatorSet::add_validator(Origin::signed(1), 4), BadOrigin);
+		assert_noop!(ValidatorSet::remove_validator(Origin::signed(1), 3), BadOrigin);
+	});
+}
+
+#[test]
+fn invalid_changes_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(ValidatorSet::add_validator(Origin::root(), 1), Error::<Test>::AlreadyValidator);
+		assert_noop!(ValidatorSet::remove_validator(Origin::root(), 4), Error::<Test>::NotValidator);
+
+		assert_ok!(ValidatorSet::remove_validator(Origin::root(), 3));
+		assert_noop!(
+			ValidatorSet::remove_validator(Origin::root(), 2),
+			Error::<Test>::TooFewValidators,
+		);
+	});
+}
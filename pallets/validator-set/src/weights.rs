@@ -0,0 +1,57 @@
+//! Weights for pallet_validator_set
+//!
+//! These values are estimates, not benchmark results. Each call is charged one
+//! `ExtrinsicBaseWeight` for its execution, plus the database reads and writes it makes, priced
+//! by `DbWeight`. Replace them with the output of
+//!
+//! ```text
+//! ./target/release/node-template benchmark --chain=dev --steps=50 --repeat=20 \
+//!     --pallet=pallet_validator_set --extrinsic=* --execution=wasm --wasm-execution=compiled \
+//!     --heap-pages=4096 --output=./pallets/validator-set/src/weights.rs
+//! ```
+//!
+//! on reference hardware before relying on them.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{Weight, constants::{ExtrinsicBaseWeight, RocksDbWeight}},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_validator_set.
+pub trait WeightInfo {
+	fn add_validator() -> Weight;
+	fn remove_validator() -> Weight;
+}
+
+/// Weights for pallet_validator_set using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn add_validator() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn remove_validator() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn add_validator() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn remove_validator() -> Weight {
+		ExtrinsicBaseWeight::get()
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+}
@@ -0,0 +1,43 @@
+//! Identification of the validators behind Aura authorities.
+//!
+//! Pallets that report offences by Aura authorities take an [`IdentifyAuthority`] in their
+//! config to turn the authority into the offender they report. [`SessionIdentification`]
+//! implements it for runtimes that manage their validators with `pallet_session`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_runtime::traits::Convert;
+use sp_staking::SessionIndex;
+use sp_std::marker::PhantomData;
+
+/// Identifies the validators behind Aura authorities, so that offences can be reported against
+/// them.
+pub trait IdentifyAuthority<Offender> {
+	/// The validator behind the authority at `index` in the Aura authorities, along with the
+	/// current session index and the number of validators in it.
+	fn identify(index: u32) -> Option<(Offender, SessionIndex, u32)>;
+}
+
+/// Identifies authorities through `pallet_session` and its historical data.
+///
+/// The session pallet hands its validators to Aura in order, so the index of an Aura authority
+/// is also its index among the session's validators.
+pub struct SessionIdentification<T>(PhantomData<T>);
+
+impl<T: pallet_session::historical::Config>
+	IdentifyAuthority<pallet_session::historical::IdentificationTuple<T>>
+	for SessionIdentification<T>
+{
+	fn identify(
+		index: u32,
+	) -> Option<(pallet_session::historical::IdentificationTuple<T>, SessionIndex, u32)> {
+		let validators = <pallet_session::Module<T>>::validators();
+		let validator = validators.get(index as usize)?.clone();
+		let full_identification = T::FullIdentificationOf::convert(validator.clone())?;
+		Some((
+			(validator, full_identification),
+			<pallet_session::Module<T>>::current_index(),
+			validators.len() as u32,
+		))
+	}
+}
@@ -0,0 +1,41 @@
+//! Runtime API definition for the scheduler agenda RPC.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Codec, Decode, Encode};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// A task in the agenda of `pallet_scheduler`.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AgendaEntry<BlockNumber> {
+	/// The block the task is dispatched in.
+	pub when: BlockNumber,
+	/// The index of the task in the agenda of that block; `(when, index)` identifies an
+	/// anonymous task to `cancel`.
+	pub index: u32,
+	/// The name of the task, for named tasks.
+	pub id: Option<Bytes>,
+	/// The priority of the task, lower being more important.
+	pub priority: u8,
+	/// The SCALE-encoded call.
+	pub call: Bytes,
+	/// The period and remaining repetitions of a periodic task.
+	pub periodic: Option<(BlockNumber, u32)>,
+	/// The SCALE-encoded origin the call is dispatched from.
+	pub origin: Bytes,
+}
+
+sp_api::decl_runtime_apis! {
+	pub trait SchedulerAgendaApi<BlockNumber> where
+		BlockNumber: Codec,
+	{
+		/// All pending tasks, ordered by the block they are due in.
+		fn agenda() -> Vec<AgendaEntry<BlockNumber>>;
+	}
+}
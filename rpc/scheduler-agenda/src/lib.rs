@@ -0,0 +1,67 @@
+//! RPC interface listing the pending tasks of the scheduler.
+
+use std::sync::Arc;
+use codec::Codec;
+use sp_blockchain::HeaderBackend;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_api::ProvideRuntimeApi;
+pub use scheduler_agenda_rpc_runtime_api::{AgendaEntry, SchedulerAgendaApi as SchedulerAgendaRuntimeApi};
+
+#[rpc]
+pub trait SchedulerAgendaApi<BlockHash, BlockNumber> {
+	/// Returns the tasks pending in the scheduler at the given block (or the best block).
+	#[rpc(name = "scheduler_agenda")]
+	fn agenda(&self, at: Option<BlockHash>) -> Result<Vec<AgendaEntry<BlockNumber>>>;
+}
+
+/// A struct that implements the [`SchedulerAgendaApi`].
+pub struct SchedulerAgenda<C, P> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<P>,
+}
+
+impl<C, P> SchedulerAgenda<C, P> {
+	/// Create new `SchedulerAgenda` with the given reference to the client.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+	/// The call to runtime failed.
+	RuntimeError,
+}
+
+impl From<Error> for i64 {
+	fn from(e: Error) -> i64 {
+		match e {
+			Error::RuntimeError => 1,
+		}
+	}
+}
+
+impl<C, Block, BlockNumber> SchedulerAgendaApi<<Block as BlockT>::Hash, BlockNumber>
+	for SchedulerAgenda<C, Block>
+where
+	Block: BlockT,
+	C: 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: SchedulerAgendaRuntimeApi<Block, BlockNumber>,
+	BlockNumber: Codec,
+{
+	fn agenda(&self, at: Option<<Block as BlockT>::Hash>) -> Result<Vec<AgendaEntry<BlockNumber>>> {
+		let api = self.client.runtime_api();
+		let at = BlockId::hash(at.unwrap_or_else(||
+			// If the block hash is not supplied assume the best block.
+			self.client.info().best_hash
+		));
+
+		api.agenda(&at).map_err(|e| RpcError {
+			code: ErrorCode::ServerError(Error::RuntimeError.into()),
+			message: "Unable to query the scheduler agenda.".into(),
+			data: Some(format!("{:?}", e).into()),
+		})
+	}
+}
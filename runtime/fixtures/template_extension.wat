@@ -0,0 +1,46 @@
+;; Forwards its input to the runtime's chain extension.
+;;
+;; The input is the function id as a little-endian `u32`, followed by the input of the function.
+;; The contract returns the function's return value as a little-endian `u32`, followed by what
+;; the function wrote to the output buffer.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32))
+	)
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) size of the input buffer
+	(data (i32.const 0) "\00\01")
+
+	;; [4, 8) size of the output buffer
+	(data (i32.const 4) "\00\01")
+
+	;; [8, 264) input buffer: function id, then its input
+
+	;; [264, 268) return value of the function
+
+	;; [268, 524) output buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 8) (i32.const 0))
+		(i32.store
+			(i32.const 264)
+			(call $seal_call_chain_extension
+				(i32.load (i32.const 8))
+				(i32.const 12)
+				(i32.sub (i32.load (i32.const 0)) (i32.const 4))
+				(i32.const 268)
+				(i32.const 4)
+			)
+		)
+		(call $seal_return
+			(i32.const 0)
+			(i32.const 264)
+			(i32.add (i32.load (i32.const 4)) (i32.const 4))
+		)
+	)
+)
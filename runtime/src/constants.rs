@@ -0,0 +1,45 @@
+//! A set of constant values used in the runtime.
+
+/// Money matters.
+pub mod currency {
+	use crate::Balance;
+
+	pub const MILLICENTS: Balance = 1_000_000_000;
+	pub const CENTS: Balance = 1_000 * MILLICENTS;
+	pub const DOLLARS: Balance = 100 * CENTS;
+
+	/// The deposit for storing `items` items taking `bytes` bytes in total.
+	pub const fn deposit(items: u32, bytes: u32) -> Balance {
+		items as Balance * 15 * CENTS + (bytes as Balance) * 6 * CENTS
+	}
+}
+
+/// Fee-related.
+pub mod fee {
+	use crate::Balance;
+	use frame_support::weights::{
+		constants::ExtrinsicBaseWeight, WeightToFeeCoefficient, WeightToFeeCoefficients,
+		WeightToFeePolynomial,
+	};
+	use smallvec::smallvec;
+	use sp_runtime::Perbill;
+
+	/// Maps weight to fee, such that the base weight of an extrinsic costs a tenth of a cent.
+	///
+	/// The polynomial is linear for now: the fee multiplier already makes fees grow with
+	/// congestion, and a steeper curve would mostly punish large but legitimate calls.
+	pub struct WeightToFee;
+	impl WeightToFeePolynomial for WeightToFee {
+		type Balance = Balance;
+		fn polynomial() -> WeightToFeeCoefficients<Self::Balance> {
+			let p = super::currency::CENTS;
+			let q = 10 * Balance::from(ExtrinsicBaseWeight::get());
+			smallvec![WeightToFeeCoefficient {
+				degree: 1,
+				negative: false,
+				coeff_frac: Perbill::from_rational_approximation(p % q, q),
+				coeff_integer: p / q,
+			}]
+		}
+	}
+}
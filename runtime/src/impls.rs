@@ -0,0 +1,161 @@
+//! Some configurable implementations as associated type for the runtime.
+
+use crate::{
+	AccountId, AssetId, Assets, Authorship, Balance, Balances, BlockNumber, Call, Origin,
+	OriginCaller, Runtime, TemplateModule, Treasury, UncheckedExtrinsic, ValidatorSet,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	storage::IterableStorageMap,
+	dispatch::{DispatchResult, Dispatchable},
+	traits::{Contains, Currency, Get, Imbalance, OnUnbalanced},
+};
+use pallet_asset_tx_payment::AssetTransfer;
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig, UncheckedFrom,
+};
+use scheduler_agenda_rpc_runtime_api::AgendaEntry;
+use sp_runtime::{DispatchError, MultiAddress};
+use template::WeightInfo;
+use sp_std::prelude::*;
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// The current validators, which run the template off-chain worker.
+pub struct Validators;
+impl Contains<AccountId> for Validators {
+	fn contains(who: &AccountId) -> bool {
+		ValidatorSet::validators().contains(who)
+	}
+
+	fn sorted_members() -> Vec<AccountId> {
+		let mut validators = ValidatorSet::validators();
+		validators.sort();
+		validators
+	}
+}
+
+/// Credits the author of the current block, or the treasury if the author is unknown.
+///
+/// `Authorship::author` returns the default account when the block has no Aura pre-runtime
+/// digest, e.g. for fees charged outside of block execution.
+pub struct Author;
+impl OnUnbalanced<NegativeImbalance> for Author {
+	fn on_nonzero_unbalanced(amount: NegativeImbalance) {
+		let author = Authorship::author();
+		if author == AccountId::default() {
+			Treasury::on_unbalanced(amount);
+		} else {
+			Balances::resolve_creating(&author, amount);
+		}
+	}
+}
+
+/// Splits transaction fees between the treasury and the block author.
+///
+/// Fees go 80% to the treasury and 20% to the author; tips go entirely to the author.
+pub struct DealWithFees;
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance>) {
+		if let Some(fees) = fees_then_tips.next() {
+			let mut split = fees.ration(80, 20);
+			if let Some(tips) = fees_then_tips.next() {
+				tips.merge_into(&mut split.1);
+			}
+			Treasury::on_unbalanced(split.0);
+			Author::on_unbalanced(split.1);
+		}
+	}
+}
+
+/// Moves the fees paid in assets with `pallet_assets` transfers.
+pub struct TransferAssets;
+impl AssetTransfer<AccountId, AssetId, Balance> for TransferAssets {
+	fn transfer(asset: AssetId, from: &AccountId, to: &AccountId, amount: Balance) -> DispatchResult {
+		Assets::transfer(Origin::signed(from.clone()), asset, MultiAddress::Id(to.clone()), amount)
+	}
+}
+
+/// The asset `uxt` pays its fees in, if not the native currency.
+pub fn fee_asset(uxt: &UncheckedExtrinsic) -> Option<AssetId> {
+	uxt.signature.as_ref().and_then(|(_, _, extra)| extra.6.asset_id())
+}
+
+/// Gives contracts access to the template pallet.
+///
+/// - Function `1` takes an encoded `AccountId` and returns the encoded `Option<u32>` it stored.
+/// - Function `2` takes an encoded `u32` and stores it for the calling contract, which pays the
+///   deposit like any other account. It returns `0` on success and `1` if the value is out of
+///   bounds; other failures trap the contract.
+///
+/// Function `2` dispatches `do_something` as a `Call` signed by the contract, so it is subject to
+/// the runtime's `BaseCallFilter`, e.g. in maintenance mode, like calls from any other account.
+pub struct TemplateExtension;
+impl ChainExtension for TemplateExtension {
+	fn call<E: Ext>(func_id: u32, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+	where
+		<E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+	{
+		let mut env = env.buf_in_buf_out();
+		match func_id {
+			1 => {
+				env.charge_weight(<Runtime as frame_system::Config>::DbWeight::get().reads(1))?;
+				let who: AccountId = env.read_as()?;
+				env.write(&TemplateModule::value_of(&who).encode(), false, None)?;
+			},
+			2 => {
+				env.charge_weight(<Runtime as template::Config>::WeightInfo::do_something())?;
+				let value: u32 = env.read_as()?;
+				let contract = AccountId::decode(&mut env.ext().address().as_ref())
+					.map_err(|_| DispatchError::Other("contract address is not an AccountId"))?;
+				let call = Call::TemplateModule(template::Call::do_something(value));
+				if let Err(e) = call.dispatch(Origin::signed(contract)) {
+					let out_of_bounds = e.error == template::Error::<Runtime>::ValueTooLow.into() ||
+						e.error == template::Error::<Runtime>::ValueTooHigh.into();
+					return if out_of_bounds { Ok(RetVal::Converging(1)) } else { Err(e.error) };
+				}
+			},
+			_ => return Err(DispatchError::Other("unknown template chain extension function")),
+		}
+		Ok(RetVal::Converging(0))
+	}
+}
+
+/// The layout of `pallet_scheduler::Scheduled`, whose fields are private.
+#[derive(Decode)]
+struct ScheduledLayout {
+	maybe_id: Option<Vec<u8>>,
+	priority: u8,
+	call: Call,
+	maybe_periodic: Option<(BlockNumber, u32)>,
+	origin: OriginCaller,
+}
+
+/// All tasks pending in the scheduler, for the `SchedulerAgendaApi`.
+///
+/// This iterates the whole agenda and is meant to be called from the runtime API only. It panics
+/// if a task does not decode as `ScheduledLayout`, so that a change of the scheduler's layout
+/// fails the call instead of hiding tasks.
+pub fn scheduler_agenda() -> Vec<AgendaEntry<BlockNumber>> {
+	let mut agenda = pallet_scheduler::Agenda::<Runtime>::iter().collect::<Vec<_>>();
+	agenda.sort_by_key(|(when, _)| *when);
+
+	agenda.into_iter()
+		.flat_map(|(when, tasks)| {
+			tasks.into_iter().enumerate().filter_map(move |(index, task)| {
+				// Cancelled tasks leave an empty slot behind.
+				let task = ScheduledLayout::decode(&mut &task?.encode()[..])
+					.expect("`ScheduledLayout` matches `pallet_scheduler::Scheduled`; qed");
+				Some(AgendaEntry {
+					when,
+					index: index as u32,
+					id: task.maybe_id.map(Into::into),
+					priority: task.priority,
+					call: task.call.encode().into(),
+					periodic: task.maybe_periodic,
+					origin: task.origin.encode().into(),
+				})
+			})
+		})
+		.collect()
+}
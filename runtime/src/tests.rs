@@ -0,0 +1,823 @@
+//! Tests of the runtime configuration that involve several pallets.
+
+use crate::*;
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	dispatch::Dispatchable,
+	traits::{Currency, OnFinalize, OnInitialize, OnRuntimeUpgrade, OnUnbalanced},
+	weights::{GetDispatchInfo, WeightToFeePolynomial},
+};
+use pallet_grandpa::fg_primitives::{
+	self, runtime_decl_for_GrandpaApi::GrandpaApi, EquivocationProof, SetId,
+};
+use sp_core::{
+	H256,
+	offchain::{testing::TestTransactionPoolExt, TransactionPoolExt},
+};
+use sp_consensus_aura::AURA_ENGINE_ID;
+use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+use sp_runtime::traits::{Hash as _, Header as _};
+
+/// The genesis validators: their accounts and Aura keys, and their GRANDPA keys.
+const VALIDATORS: [(Sr25519Keyring, Ed25519Keyring); 3] = [
+	(Sr25519Keyring::Alice, Ed25519Keyring::Alice),
+	(Sr25519Keyring::Bob, Ed25519Keyring::Bob),
+	(Sr25519Keyring::Charlie, Ed25519Keyring::Charlie),
+];
+
+fn new_test_ext() -> sp_io::TestExternalities {
+	let accounts = VALIDATORS.iter().map(|(sr, _)| sr.to_account_id()).collect::<Vec<_>>();
+	let storage = GenesisConfig {
+		frame_system: Some(SystemConfig {
+			code: vec![],
+			changes_trie_config: Default::default(),
+		}),
+		pallet_aura: Some(AuraConfig { authorities: vec![] }),
+		pallet_grandpa: Some(GrandpaConfig { authorities: vec![] }),
+		pallet_balances: Some(BalancesConfig {
+			balances: accounts.iter().cloned().map(|a| (a, 1 << 60)).collect(),
+		}),
+		pallet_indices: Some(IndicesConfig {
+			indices: accounts.iter().cloned().enumerate().map(|(i, a)| (i as u32, a)).collect(),
+		}),
+		pallet_validator_set: Some(ValidatorSetConfig { validators: accounts }),
+		pallet_session: Some(SessionConfig {
+			keys: VALIDATORS.iter().map(|(sr, ed)| {
+				let keys = opaque::SessionKeys {
+					aura: sr.public().into(),
+					grandpa: ed.public().into(),
+				};
+				(sr.to_account_id(), sr.to_account_id(), keys)
+			}).collect(),
+		}),
+		pallet_sudo: Some(SudoConfig { key: VALIDATORS[0].0.to_account_id() }),
+		template: None,
+		pallet_collective_Instance1: Some(Default::default()),
+		pallet_membership_Instance1: Some(CouncilMembershipConfig {
+			members: VALIDATORS.iter().map(|(sr, _)| sr.to_account_id()).collect(),
+			phantom: Default::default(),
+		}),
+		pallet_democracy: Some(Default::default()),
+		pallet_treasury: Some(Default::default()),
+		pallet_contracts: Some(Default::default()),
+		pallet_identity_registrars: Some(IdentityRegistrarsConfig {
+			registrars: vec![VALIDATORS[0].0.to_account_id()],
+		}),
+		pallet_names: Some(NamesConfig {
+			names: vec![(VALIDATORS[0].0.to_account_id(), b"alice".to_vec())],
+		}),
+	}.build_storage().unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	// Events are not deposited in the genesis block.
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// Two conflicting prevotes of `offender` in round 1 of `set_id`.
+fn equivocation_proof(
+	set_id: SetId,
+	offender: Ed25519Keyring,
+) -> EquivocationProof<Hash, BlockNumber> {
+	let round = 1;
+	let signed_prevote = |target_hash| {
+		let prevote = finality_grandpa::Prevote { target_hash, target_number: 10 };
+		let message = finality_grandpa::Message::Prevote(prevote.clone());
+		let payload = fg_primitives::localized_payload(round, set_id, &message);
+		(prevote, offender.sign(&payload).into())
+	};
+
+	EquivocationProof::new(
+		set_id,
+		fg_primitives::Equivocation::Prevote(finality_grandpa::Equivocation {
+			round_number: round,
+			identity: offender.public().into(),
+			first: signed_prevote(H256::repeat_byte(1)),
+			second: signed_prevote(H256::repeat_byte(2)),
+		}),
+	)
+}
+
+#[test]
+fn grandpa_equivocations_are_reported_and_handled() {
+	let (offender_account, offender) = (VALIDATORS[0].0.to_account_id(), VALIDATORS[0].1);
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let mut ext = new_test_ext();
+	ext.register_extension(TransactionPoolExt::new(pool));
+
+	ext.execute_with(|| {
+		assert!(Session::validators().contains(&offender_account));
+		let set_id = Grandpa::current_set_id();
+
+		// What the node's GRANDPA voter does when it sees the equivocation.
+		let key_owner_proof = <Runtime as GrandpaApi<Block>>::generate_key_ownership_proof(
+			set_id,
+			offender.public().into(),
+		).expect("the offender is a current authority");
+		assert_eq!(
+			<Runtime as GrandpaApi<Block>>::submit_report_equivocation_unsigned_extrinsic(
+				equivocation_proof(set_id, offender),
+				key_owner_proof,
+			),
+			Some(()),
+		);
+
+		// The report is submitted as an unsigned transaction; include it in the block.
+		let tx = pool_state.write().transactions.pop().expect("the report was submitted");
+		let xt = UncheckedExtrinsic::decode(&mut &*tx).unwrap();
+		assert!(xt.signature.is_none());
+		assert_eq!(Executive::apply_extrinsic(xt), Ok(Ok(())));
+
+		// The offender is disabled right away and leaves the set at a later session.
+		assert_eq!(Session::disabled_validators(), vec![0]);
+		assert!(!ValidatorSet::validators().contains(&offender_account));
+		assert!(System::events().iter().any(|record| {
+			matches!(record.event, Event::pallet_offences(pallet_offences::Event::Offence(..)))
+		}));
+	});
+}
+
+/// A header for `slot`, distinguished by `state_root` and sealed by `author`.
+fn aura_header(slot: u64, state_root: u8, author: Sr25519Keyring) -> Header {
+	let digest = generic::Digest {
+		logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
+	};
+	let mut header = Header::new(1, H256::zero(), H256::repeat_byte(state_root), H256::zero(), digest);
+	let signature = author.sign(header.hash().as_ref());
+	header.digest_mut().push(DigestItem::Seal(AURA_ENGINE_ID, signature.encode()));
+	header
+}
+
+#[test]
+fn aura_equivocations_are_reported_as_offences() {
+	new_test_ext().execute_with(|| {
+		// Slot 4 of 3 authorities belongs to the second validator.
+		let (offender, offender_account) = (VALIDATORS[1].0, VALIDATORS[1].0.to_account_id());
+		assert!(Session::validators().contains(&offender_account));
+
+		assert_ok!(AuraEquivocation::report_equivocation_unsigned(
+			Origin::none(),
+			aura_header(4, 1, offender),
+			aura_header(4, 2, offender),
+		));
+
+		// The offender is disabled right away and leaves the set at a later session.
+		assert_eq!(Session::disabled_validators(), vec![1]);
+		assert!(!ValidatorSet::validators().contains(&offender_account));
+		assert!(System::events().iter().any(|record| {
+			matches!(
+				record.event,
+				Event::pallet_offences(pallet_offences::Event::Offence(kind, _, _))
+					if kind == *b"aura:equivocatio"
+			)
+		}));
+	});
+}
+
+#[test]
+fn a_council_majority_can_act_as_admin() {
+	new_test_ext().execute_with(|| {
+		let [alice, bob, charlie] = [
+			Sr25519Keyring::Alice.to_account_id(),
+			Sr25519Keyring::Bob.to_account_id(),
+			Sr25519Keyring::Charlie.to_account_id(),
+		];
+		let proposal = Call::TemplateModule(template::Call::set_bounds(10, 20));
+		let length = proposal.encode().len() as u32;
+		let hash = BlakeTwo256::hash_of(&proposal);
+
+		// A single member is not a majority of three.
+		assert_ok!(Council::propose(Origin::signed(alice.clone()), 1, Box::new(proposal.clone()), length));
+		assert_eq!(TemplateModule::bounds(), (0, u32::max_value()));
+
+		// Two of them are.
+		assert_ok!(Council::propose(Origin::signed(alice), 2, Box::new(proposal.clone()), length));
+		assert_ok!(Council::vote(Origin::signed(bob), hash, 0, true));
+		assert_ok!(Council::close(
+			Origin::signed(charlie),
+			hash,
+			0,
+			proposal.get_dispatch_info().weight,
+			length,
+		));
+		assert_eq!(TemplateModule::bounds(), (10, 20));
+	});
+}
+
+#[test]
+fn remove_sudo_retires_the_sudo_key() {
+	new_test_ext().execute_with(|| {
+		let alice = Sr25519Keyring::Alice.to_account_id();
+		let remark = Box::new(Call::System(frame_system::Call::remark(vec![])));
+		assert_ok!(Sudo::sudo(Origin::signed(alice.clone()), remark.clone()));
+
+		RemoveSudo::on_runtime_upgrade();
+
+		assert_noop!(
+			Sudo::sudo(Origin::signed(alice), remark),
+			pallet_sudo::Error::<Runtime>::RequireSudo,
+		);
+	});
+}
+
+#[test]
+fn scheduled_tasks_are_listed_until_dispatched() {
+	new_test_ext().execute_with(|| {
+		let call = Call::TemplateModule(template::Call::set_bounds(10, 20));
+		assert_ok!(Scheduler::schedule_named(
+			Origin::root(),
+			b"bounds".to_vec(),
+			5,
+			None,
+			0,
+			Box::new(call.clone()),
+		));
+
+		let agenda = impls::scheduler_agenda();
+		assert_eq!(agenda.len(), 1);
+		assert_eq!((agenda[0].when, agenda[0].index), (5, 0));
+		assert_eq!(agenda[0].id, Some(b"bounds".to_vec().into()));
+		assert_eq!(agenda[0].call, call.encode().into());
+		assert_eq!(agenda[0].origin, OriginCaller::system(frame_system::RawOrigin::Root).encode().into());
+
+		// Tasks keep the origin they were scheduled with, which satisfies the admin origin here.
+		Scheduler::on_initialize(5);
+		assert_eq!(TemplateModule::bounds(), (10, 20));
+		assert!(impls::scheduler_agenda().is_empty());
+	});
+}
+
+#[test]
+fn periodic_and_anonymous_tasks_are_listed() {
+	new_test_ext().execute_with(|| {
+		let call = Call::System(frame_system::Call::remark(vec![]));
+		assert_ok!(Scheduler::schedule(Origin::root(), 5, Some((10, 3)), 7, Box::new(call.clone())));
+
+		let agenda = impls::scheduler_agenda();
+		assert_eq!(agenda.len(), 1);
+		assert_eq!((agenda[0].when, agenda[0].index), (5, 0));
+		assert_eq!(agenda[0].id, None);
+		assert_eq!(agenda[0].priority, 7);
+		// The scheduler stores the number of repetitions left after this one.
+		assert_eq!(agenda[0].periodic, Some((10, 2)));
+		assert_eq!(agenda[0].call, call.encode().into());
+	});
+}
+
+/// Run `blocks` blocks whose normal dispatch class is filled to `fullness`, returning the fee
+/// multiplier after each of them.
+fn multipliers_with_fullness(blocks: u32, fullness: Perbill) -> Vec<Multiplier> {
+	let max_normal = BlockWeights::get().get(DispatchClass::Normal).max_total.unwrap();
+	(0..blocks).map(|_| {
+		let number = System::block_number() + 1;
+		System::initialize(&number, &Default::default(), &Default::default(), frame_system::InitKind::Full);
+		System::register_extra_weight_unchecked(fullness * max_normal, DispatchClass::Normal);
+		TransactionPayment::on_finalize(number);
+		System::finalize();
+		TransactionPayment::next_fee_multiplier()
+	}).collect()
+}
+
+#[test]
+fn fee_multiplier_rises_with_full_blocks_and_decays_with_empty_ones() {
+	new_test_ext().execute_with(|| {
+		let one = Multiplier::saturating_from_integer(1);
+		assert_eq!(TransactionPayment::next_fee_multiplier(), one);
+
+		let rising = multipliers_with_fullness(100, Perbill::from_percent(100));
+		assert!(rising[0] > one);
+		assert!(rising.windows(2).all(|w| w[0] < w[1]));
+
+		let decaying = multipliers_with_fullness(100, Perbill::zero());
+		assert!(decaying[0] < rising[99]);
+		assert!(decaying.windows(2).all(|w| w[0] > w[1]));
+	});
+}
+
+#[test]
+fn fee_multiplier_never_drops_below_the_minimum() {
+	new_test_ext().execute_with(|| {
+		// Decaying all the way down takes far too many blocks; start close to the minimum.
+		pallet_transaction_payment::NextFeeMultiplier::put(MinimumMultiplier::get());
+		let multipliers = multipliers_with_fullness(10, Perbill::zero());
+		assert!(multipliers.iter().all(|m| *m == MinimumMultiplier::get()));
+	});
+}
+
+#[test]
+fn base_extrinsic_weight_costs_a_tenth_of_a_cent() {
+	let fee = WeightToFee::calc(&ExtrinsicBaseWeight::get());
+	assert!(fee <= CENTS / 10 && fee > CENTS / 10 - 10, "{}", fee);
+}
+
+/// Start block 2, authored in `slot`.
+fn initialize_block_in_slot(slot: u64) {
+	let digest = generic::Digest {
+		logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())],
+	};
+	System::initialize(&2, &Default::default(), &digest, frame_system::InitKind::Full);
+}
+
+#[test]
+fn fees_are_split_between_treasury_and_author() {
+	new_test_ext().execute_with(|| {
+		// Slot 1 of three validators is Bob's.
+		initialize_block_in_slot(1);
+		let author = Sr25519Keyring::Bob.to_account_id();
+		assert_eq!(Authorship::author(), author);
+		let treasury_before = Balances::free_balance(Treasury::account_id());
+		let author_before = Balances::free_balance(&author);
+
+		let fees = Balances::issue(1_000);
+		let tips = Balances::issue(50);
+		impls::DealWithFees::on_unbalanceds(vec![fees, tips].into_iter());
+
+		assert_eq!(Balances::free_balance(Treasury::account_id()), treasury_before + 800);
+		assert_eq!(Balances::free_balance(&author), author_before + 200 + 50);
+	});
+}
+
+#[test]
+fn fees_go_to_the_treasury_without_an_author() {
+	new_test_ext().execute_with(|| {
+		// Blocks without an Aura pre-runtime digest have no known author.
+		System::initialize(&2, &Default::default(), &Default::default(), frame_system::InitKind::Full);
+		assert_eq!(Authorship::author(), AccountId::default());
+		let treasury_before = Balances::free_balance(Treasury::account_id());
+
+		let fees = Balances::issue(1_000);
+		let tips = Balances::issue(50);
+		impls::DealWithFees::on_unbalanceds(vec![fees, tips].into_iter());
+
+		assert_eq!(Balances::free_balance(Treasury::account_id()), treasury_before + 1_050);
+		assert_eq!(Balances::free_balance(&AccountId::default()), 0);
+	});
+}
+
+#[test]
+fn dust_goes_to_the_treasury() {
+	new_test_ext().execute_with(|| {
+		let dave = Sr25519Keyring::Dave.to_account_id();
+		let treasury_before = Balances::free_balance(Treasury::account_id());
+		let _ = Balances::deposit_creating(&dave, 10 * ExistentialDeposit::get());
+
+		// Dropping below the existential deposit reaps the account; the rest is dust.
+		let _ = Balances::slash(&dave, 9 * ExistentialDeposit::get() + 1);
+
+		assert_eq!(Balances::free_balance(&dave), 0);
+		assert_eq!(
+			Balances::free_balance(Treasury::account_id()),
+			treasury_before + ExistentialDeposit::get() - 1,
+		);
+	});
+}
+
+#[test]
+fn maintenance_freezes_calls_but_not_block_production() {
+	new_test_ext().execute_with(|| {
+		let alice = Sr25519Keyring::Alice.to_account_id();
+		let transfer = Call::Balances(pallet_balances::Call::transfer(
+			Sr25519Keyring::Bob.to_account_id().into(),
+			ExistentialDeposit::get(),
+		));
+		let store = Call::TemplateModule(template::Call::do_something(1));
+
+		// Freeze transfers only.
+		assert_ok!(Maintenance::block(Origin::root(), b"Balances".to_vec(), Some(b"transfer".to_vec())));
+		assert_noop!(transfer.clone().dispatch(Origin::signed(alice.clone())), sp_runtime::DispatchError::BadOrigin);
+		assert_ok!(store.clone().dispatch(Origin::signed(alice.clone())));
+
+		// Freeze everything but the safe calls.
+		assert_ok!(Maintenance::set_maintenance_mode(Origin::root(), true));
+		assert_noop!(store.dispatch(Origin::signed(alice)), sp_runtime::DispatchError::BadOrigin);
+		assert_ok!(Call::Timestamp(pallet_timestamp::Call::set(MinimumPeriod::get())).dispatch(Origin::none()));
+	});
+}
+
+#[test]
+fn batch_all_is_atomic() {
+	new_test_ext().execute_with(|| {
+		let alice = Sr25519Keyring::Alice.to_account_id();
+		let bob = Sr25519Keyring::Bob.to_account_id();
+		let store = Call::TemplateModule(template::Call::do_something(5));
+		let transfer = |value| Call::Balances(pallet_balances::Call::transfer(bob.clone().into(), value));
+
+		// The transfer cannot be afforded, so nothing is stored either.
+		assert!(Utility::batch_all(Origin::signed(alice.clone()), vec![store.clone(), transfer(1 << 61)]).is_err());
+		assert_eq!(TemplateModule::value_of(&alice), None);
+
+		let bob_before = Balances::free_balance(&bob);
+		assert_ok!(Utility::batch_all(Origin::signed(alice.clone()), vec![store, transfer(DOLLARS)]));
+		assert_eq!(TemplateModule::value_of(&alice), Some(5));
+		assert_eq!(Balances::free_balance(&bob), bob_before + DOLLARS);
+	});
+}
+
+#[test]
+fn proxy_types_restrict_the_calls_proxies_can_make() {
+	new_test_ext().execute_with(|| {
+		let [alice, bob, charlie] = [
+			Sr25519Keyring::Alice.to_account_id(),
+			Sr25519Keyring::Bob.to_account_id(),
+			Sr25519Keyring::Charlie.to_account_id(),
+		];
+		assert_ok!(Proxy::add_proxy(Origin::signed(alice.clone()), bob.clone(), ProxyType::TemplateOnly, 0));
+		assert_ok!(Proxy::add_proxy(Origin::signed(alice.clone()), charlie.clone(), ProxyType::NonTransfer, 0));
+
+		let store = |value| Box::new(Call::TemplateModule(template::Call::do_something(value)));
+		let transfer = Box::new(Call::Balances(pallet_balances::Call::transfer(
+			Sr25519Keyring::Dave.to_account_id().into(),
+			DOLLARS,
+		)));
+		let alice_before = Balances::total_balance(&alice);
+
+		// Both can use the template pallet on Alice's behalf, also in a batch.
+		assert_ok!(Proxy::proxy(Origin::signed(bob.clone()), alice.clone(), None, store(7)));
+		assert_eq!(TemplateModule::value_of(&alice), Some(7));
+		let batch = Box::new(Call::Utility(pallet_utility::Call::batch(vec![*store(8)])));
+		assert_ok!(Proxy::proxy(Origin::signed(charlie.clone()), alice.clone(), None, batch));
+		assert_eq!(TemplateModule::value_of(&alice), Some(8));
+
+		// Neither can move her funds; the failed calls are reported in events.
+		assert_ok!(Proxy::proxy(Origin::signed(bob.clone()), alice.clone(), None, transfer.clone()));
+		assert_ok!(Proxy::proxy(Origin::signed(charlie), alice.clone(), None, transfer));
+		assert_eq!(Balances::total_balance(&alice), alice_before);
+
+		// And a template-only proxy cannot escalate itself.
+		let add_any = Box::new(Call::Proxy(pallet_proxy::Call::add_proxy(bob.clone(), ProxyType::Any, 0)));
+		assert_ok!(Proxy::proxy(Origin::signed(bob.clone()), alice.clone(), None, add_any));
+		assert!(Proxy::proxies(&alice).0.iter().all(|p| p.proxy_type != ProxyType::Any));
+	});
+}
+
+/// Whether a `NonTransfer` proxy may make `call`.
+fn non_transfer_allows(call: Call) -> bool {
+	use frame_support::traits::InstanceFilter;
+	ProxyType::NonTransfer.filter(&call)
+}
+
+#[test]
+fn non_transfer_proxies_allow_calls_that_move_no_funds() {
+	assert!(non_transfer_allows(Call::System(frame_system::Call::remark(vec![]))));
+	assert!(non_transfer_allows(Call::TemplateModule(template::Call::do_something(1))));
+	assert!(non_transfer_allows(Call::Indices(pallet_indices::Call::claim(1))));
+	assert!(non_transfer_allows(Call::Contracts(pallet_contracts::Call::put_code(vec![]))));
+}
+
+#[test]
+fn non_transfer_proxies_cannot_use_balances() {
+	let dave = Sr25519Keyring::Dave.to_account_id();
+	assert!(!non_transfer_allows(Call::Balances(pallet_balances::Call::transfer(dave.clone().into(), 1))));
+	assert!(!non_transfer_allows(Call::Balances(pallet_balances::Call::transfer_keep_alive(dave.into(), 1))));
+}
+
+#[test]
+fn non_transfer_proxies_cannot_transfer_indices() {
+	let dave = Sr25519Keyring::Dave.to_account_id();
+	assert!(!non_transfer_allows(Call::Indices(pallet_indices::Call::transfer(dave, 1))));
+}
+
+#[test]
+fn non_transfer_proxies_cannot_use_assets() {
+	let dave = Sr25519Keyring::Dave.to_account_id();
+	assert!(!non_transfer_allows(Call::Assets(pallet_assets::Call::transfer(1, dave.into(), 1))));
+}
+
+#[test]
+fn non_transfer_proxies_cannot_call_or_instantiate_contracts() {
+	let dave = Sr25519Keyring::Dave.to_account_id();
+	assert!(!non_transfer_allows(Call::Contracts(pallet_contracts::Call::call(dave.into(), 1, 0, vec![]))));
+	assert!(!non_transfer_allows(Call::Contracts(pallet_contracts::Call::instantiate(
+		1,
+		0,
+		Default::default(),
+		vec![],
+		vec![],
+	))));
+}
+
+#[test]
+fn non_transfer_proxies_cannot_execute_multisig_calls() {
+	let dave = Sr25519Keyring::Dave.to_account_id();
+	let remark = Call::System(frame_system::Call::remark(vec![]));
+	assert!(!non_transfer_allows(Call::Multisig(pallet_multisig::Call::as_multi(
+		2,
+		vec![dave.clone()],
+		None,
+		remark.encode(),
+		false,
+		0,
+	))));
+	assert!(!non_transfer_allows(Call::Multisig(pallet_multisig::Call::as_multi_threshold_1(
+		vec![dave],
+		Box::new(remark),
+	))));
+}
+
+#[test]
+fn non_transfer_proxies_cannot_propose_treasury_spends() {
+	let dave = Sr25519Keyring::Dave.to_account_id();
+	assert!(!non_transfer_allows(Call::Treasury(pallet_treasury::Call::propose_spend(1, dave.into()))));
+}
+
+#[test]
+fn non_transfer_proxies_cannot_use_sudo() {
+	let remark = Call::System(frame_system::Call::remark(vec![]));
+	assert!(!non_transfer_allows(Call::Sudo(pallet_sudo::Call::sudo(Box::new(remark)))));
+}
+
+#[test]
+fn proxy_type_supersets() {
+	assert!(ProxyType::Any.is_superset(&ProxyType::NonTransfer));
+	assert!(ProxyType::NonTransfer.is_superset(&ProxyType::TemplateOnly));
+	assert!(!ProxyType::TemplateOnly.is_superset(&ProxyType::NonTransfer));
+	assert!(!ProxyType::NonTransfer.is_superset(&ProxyType::Any));
+}
+
+#[test]
+fn randomness_comes_from_revealed_seeds_once_there_are_any() {
+	new_test_ext().execute_with(|| {
+		let subject = b"subject";
+		assert_eq!(RandomnessBeacon::random(subject), RandomnessCollectiveFlip::random(subject));
+
+		let alice: AuraId = Sr25519Keyring::Alice.public().into();
+		let seed = H256::repeat_byte(1);
+		let payload = pallet_randomness_beacon::CommitPayload {
+			authority: alice.clone(),
+			commitment: BlakeTwo256::hash_of(&seed),
+			block_number: System::block_number(),
+		};
+		let signature = Sr25519Keyring::Alice.sign(&payload.encode()).into();
+		assert_ok!(RandomnessBeacon::commit_unsigned(Origin::none(), payload, signature));
+
+		// The seed is revealed in the next round, and used once that one closes.
+		let round = RandomnessRoundLength::get();
+		let next_round = (System::block_number() / round + 1) * round;
+		System::set_block_number(next_round);
+		assert_ok!(RandomnessBeacon::reveal_unsigned(Origin::none(), alice, seed));
+		assert_eq!(RandomnessBeacon::random(subject), RandomnessCollectiveFlip::random(subject));
+
+		System::set_block_number(next_round + round);
+		RandomnessBeacon::on_initialize(next_round + round);
+		assert_ne!(RandomnessBeacon::random(subject), RandomnessCollectiveFlip::random(subject));
+	});
+}
+
+#[test]
+fn missed_reveals_are_reported_as_offences() {
+	new_test_ext().execute_with(|| {
+		let (offender, offender_account) = (VALIDATORS[2].0, VALIDATORS[2].0.to_account_id());
+		let seed = H256::repeat_byte(3);
+		let payload = pallet_randomness_beacon::CommitPayload {
+			authority: AuraId::from(offender.public()),
+			commitment: BlakeTwo256::hash_of(&seed),
+			block_number: System::block_number(),
+		};
+		let signature = offender.sign(&payload.encode()).into();
+		assert_ok!(RandomnessBeacon::commit_unsigned(Origin::none(), payload, signature));
+
+		// The seed was due in the round after the commitment.
+		let round = RandomnessRoundLength::get();
+		RandomnessBeacon::on_initialize((System::block_number() / round + 2) * round);
+
+		assert_eq!(Session::disabled_validators(), vec![2]);
+		assert!(!ValidatorSet::validators().contains(&offender_account));
+		assert!(System::events().iter().any(|record| {
+			matches!(
+				record.event,
+				Event::pallet_offences(pallet_offences::Event::Offence(kind, _, _))
+					if kind == *b"beacon:unreveal_"
+			)
+		}));
+	});
+}
+
+/// Create asset 1, worth half a native unit in fees, and give Bob 1000 `DOLLARS` of it.
+fn create_fee_asset() -> AssetId {
+	let alice = Sr25519Keyring::Alice.to_account_id();
+	let bob = Sr25519Keyring::Bob.to_account_id();
+	assert_ok!(Assets::force_create(Origin::root(), 1, alice.clone().into(), 10, 1));
+	assert_ok!(Assets::mint(Origin::signed(alice), 1, bob.into(), 1_000 * DOLLARS));
+	assert_ok!(AssetTxPayment::set_conversion_rate(
+		Origin::root(),
+		1,
+		Some(sp_runtime::FixedU128::saturating_from_integer(2)),
+	));
+	1
+}
+
+#[test]
+fn fees_can_be_paid_in_assets() {
+	use sp_runtime::traits::SignedExtension;
+
+	new_test_ext().execute_with(|| {
+		let asset = create_fee_asset();
+		let bob = Sr25519Keyring::Bob.to_account_id();
+		let native_before = Balances::free_balance(&bob);
+		let call = Call::System(frame_system::Call::remark(vec![]));
+		let info = call.get_dispatch_info();
+		let fee = TransactionPayment::compute_fee(100, &info, 0);
+
+		assert_ok!(pallet_asset_tx_payment::ChargeAssetTxPayment::<Runtime>::new(0, Some(asset))
+			.pre_dispatch(&bob, &call, &info, 100));
+		assert_eq!(Assets::balance(asset, bob.clone()), 1_000 * DOLLARS - 2 * fee);
+		assert_eq!(Assets::balance(asset, Treasury::account_id()), 2 * fee);
+		assert_eq!(Balances::free_balance(&bob), native_before);
+	});
+}
+
+#[test]
+fn accounts_holding_only_assets_cannot_pay_fees_with_them() {
+	use sp_runtime::traits::SignedExtension;
+
+	new_test_ext().execute_with(|| {
+		let asset = create_fee_asset();
+		let alice = Sr25519Keyring::Alice.to_account_id();
+		let dave = Sr25519Keyring::Dave.to_account_id();
+		assert_ok!(Assets::mint(Origin::signed(alice.clone()), asset, dave.clone().into(), 10 * DOLLARS));
+		assert_eq!(System::providers(&dave), 0);
+
+		let call = Call::System(frame_system::Call::remark(vec![]));
+		let info = call.get_dispatch_info();
+		let charge = pallet_asset_tx_payment::ChargeAssetTxPayment::<Runtime>::new(0, Some(asset));
+		assert!(charge.clone().pre_dispatch(&dave, &call, &info, 100).is_err());
+
+		// Once Dave has a native balance, the asset can pay.
+		assert_ok!(Balances::transfer(Origin::signed(alice), dave.clone().into(), DOLLARS));
+		assert_ok!(charge.pre_dispatch(&dave, &call, &info, 100));
+	});
+}
+
+/// A remark by Bob paying its fee in `asset`, for fee queries, which do not check the signature.
+fn remark_from_bob(asset: Option<AssetId>) -> UncheckedExtrinsic {
+	let extra: SignedExtra = (
+		frame_system::CheckSpecVersion::new(),
+		frame_system::CheckTxVersion::new(),
+		frame_system::CheckGenesis::new(),
+		frame_system::CheckEra::from(generic::Era::Immortal),
+		frame_system::CheckNonce::from(0),
+		frame_system::CheckWeight::new(),
+		pallet_asset_tx_payment::ChargeAssetTxPayment::new(0, asset),
+	);
+	UncheckedExtrinsic::new_signed(
+		Call::System(frame_system::Call::remark(vec![])),
+		Sr25519Keyring::Bob.to_account_id().into(),
+		Sr25519Keyring::Bob.sign(b"").into(),
+		extra,
+	)
+}
+
+#[test]
+fn fee_details_apply_the_fee_multiplier() {
+	use pallet_transaction_payment_rpc_runtime_api::runtime_decl_for_TransactionPaymentApi::TransactionPaymentApi;
+
+	new_test_ext().execute_with(|| {
+		let weight_fee = || {
+			<Runtime as TransactionPaymentApi<Block, Balance>>::query_fee_details(remark_from_bob(None), 100)
+				.inclusion_fee
+				.expect("signed extrinsics pay an inclusion fee")
+				.adjusted_weight_fee
+		};
+		let unadjusted = weight_fee();
+		assert!(unadjusted > 0);
+
+		pallet_transaction_payment::NextFeeMultiplier::put(Multiplier::saturating_from_integer(3));
+		assert_eq!(weight_fee(), 3 * unadjusted);
+	});
+}
+
+#[test]
+fn fee_queries_report_the_fee_asset() {
+	use pallet_transaction_payment_rpc_runtime_api::runtime_decl_for_TransactionPaymentApi::TransactionPaymentApi;
+
+	new_test_ext().execute_with(|| {
+		let asset = create_fee_asset();
+		let native = <Runtime as TransactionPaymentApi<Block, Balance>>::query_info(remark_from_bob(None), 100);
+		let in_asset = <Runtime as TransactionPaymentApi<Block, Balance>>::query_info(remark_from_bob(Some(asset)), 100);
+		assert!(native.partial_fee > 0);
+		assert_eq!(in_asset.partial_fee, 2 * native.partial_fee);
+
+		let details = <Runtime as TransactionPaymentApi<Block, Balance>>::query_fee_details(remark_from_bob(Some(asset)), 100);
+		assert_eq!(details.final_fee(), 2 * native.partial_fee);
+	});
+}
+
+/// Gas for the calls into the template extension fixture, far more than they need.
+const CONTRACT_GAS_LIMIT: Weight = 100_000_000_000;
+
+/// Deploy `fixtures/template_extension.wat` from Alice, returning the contract's account.
+fn deploy_template_extension_fixture() -> AccountId {
+	let alice = Sr25519Keyring::Alice.to_account_id();
+	let wasm = wat::parse_str(include_str!("../fixtures/template_extension.wat"))
+		.expect("the fixture is valid WAT");
+	let code_hash = BlakeTwo256::hash(&wasm);
+	assert_ok!(Contracts::put_code(Origin::signed(alice.clone()), wasm));
+	assert_ok!(Contracts::instantiate(
+		Origin::signed(alice.clone()),
+		100 * DOLLARS,
+		CONTRACT_GAS_LIMIT,
+		code_hash,
+		vec![],
+		vec![],
+	));
+	Contracts::contract_address(&alice, &code_hash, &[])
+}
+
+/// Call `func_id` of the template extension through the fixture at `contract`, returning the
+/// function's return value and output, or `None` if the contract trapped.
+fn call_template_extension(contract: &AccountId, func_id: u32, input: impl Encode) -> Option<(u32, Vec<u8>)> {
+	let alice = Sr25519Keyring::Alice.to_account_id();
+	let mut data = func_id.encode();
+	data.extend(input.encode());
+	let result = Contracts::bare_call(alice, contract.clone(), 0, CONTRACT_GAS_LIMIT, data);
+	let output = result.exec_result.ok()?.data;
+	let ret_val = u32::decode(&mut &output[..4]).expect("the fixture returns a u32 first");
+	Some((ret_val, output[4..].to_vec()))
+}
+
+#[test]
+fn contracts_read_and_write_template_values_through_the_chain_extension() {
+	new_test_ext().execute_with(|| {
+		let contract = deploy_template_extension_fixture();
+		let alice = Sr25519Keyring::Alice.to_account_id();
+		assert_ok!(TemplateModule::do_something(Origin::signed(alice.clone()), 7));
+
+		// Function 1 reads the value of any account.
+		let (ret_val, output) = call_template_extension(&contract, 1, &alice).expect("reading works");
+		assert_eq!(ret_val, 0);
+		assert_eq!(Option::<u32>::decode(&mut &output[..]), Ok(Some(7)));
+
+		// Function 2 stores a value for the contract.
+		assert_eq!(call_template_extension(&contract, 2, 42u32).map(|(ret_val, _)| ret_val), Some(0));
+		assert_eq!(TemplateModule::value_of(&contract), Some(42));
+		let (_, output) = call_template_extension(&contract, 1, &contract).expect("reading works");
+		assert_eq!(Option::<u32>::decode(&mut &output[..]), Ok(Some(42)));
+
+		// Values out of bounds are reported to the contract.
+		assert_ok!(TemplateModule::set_bounds(Origin::root(), 10, 20));
+		assert_eq!(call_template_extension(&contract, 2, 5u32).map(|(ret_val, _)| ret_val), Some(1));
+		assert_eq!(TemplateModule::value_of(&contract), Some(42));
+	});
+}
+
+#[test]
+fn contracts_cannot_bypass_the_call_filter_through_the_chain_extension() {
+	new_test_ext().execute_with(|| {
+		let contract = deploy_template_extension_fixture();
+		assert_ok!(Maintenance::block(Origin::root(), b"TemplateModule".to_vec(), None));
+
+		assert_eq!(call_template_extension(&contract, 2, 42u32), None);
+		assert_eq!(TemplateModule::value_of(&contract), None);
+	});
+}
+
+#[test]
+fn names_resolve_both_ways() {
+	use pallet_names_rpc_runtime_api::runtime_decl_for_NamesApi::NamesApi;
+
+	new_test_ext().execute_with(|| {
+		let alice = Sr25519Keyring::Alice.to_account_id();
+		let charlie = Sr25519Keyring::Charlie.to_account_id();
+		assert_eq!(Identity::registrars().len(), 1);
+		assert_eq!(<Runtime as NamesApi<Block, AccountId>>::account_of(b"alice".to_vec()), Some(alice.clone()));
+		assert_eq!(<Runtime as NamesApi<Block, AccountId>>::name_of(alice.clone()), Some(b"alice".to_vec()));
+
+		let info = pallet_identity::IdentityInfo {
+			display: pallet_identity::Data::Raw(b"Charlie".to_vec()),
+			..Default::default()
+		};
+		assert_ok!(Identity::set_identity(Origin::signed(charlie.clone()), info));
+		assert_ok!(Identity::request_judgement(Origin::signed(charlie.clone()), 0, 0));
+		assert_ok!(Identity::provide_judgement(
+			Origin::signed(alice),
+			0,
+			charlie.clone().into(),
+			pallet_identity::Judgement::Reasonable,
+		));
+		// Identity display names cannot be resolved back to accounts, so they are not names.
+		assert_eq!(<Runtime as NamesApi<Block, AccountId>>::name_of(charlie.clone()), None);
+		assert_eq!(<Runtime as NamesApi<Block, AccountId>>::account_of(b"Charlie".to_vec()), None);
+
+		assert_ok!(Names::set_name(Origin::signed(charlie.clone()), b"charlie".to_vec()));
+		assert_eq!(<Runtime as NamesApi<Block, AccountId>>::name_of(charlie.clone()), Some(b"charlie".to_vec()));
+		assert_eq!(<Runtime as NamesApi<Block, AccountId>>::account_of(b"charlie".to_vec()), Some(charlie));
+	});
+}
+
+#[test]
+fn transfers_can_be_sent_to_account_indices() {
+	new_test_ext().execute_with(|| {
+		let alice = Sr25519Keyring::Alice.to_account_id();
+		let charlie = Sr25519Keyring::Charlie.to_account_id();
+		// Genesis assigns indices to the endowed accounts in order.
+		assert_eq!(Indices::lookup_index(2), Some(charlie.clone()));
+
+		let charlie_before = Balances::free_balance(&charlie);
+		assert_ok!(Balances::transfer(Origin::signed(alice.clone()), Address::Index(2), DOLLARS));
+		assert_eq!(Balances::free_balance(&charlie), charlie_before + DOLLARS);
+
+		// Unassigned indices do not resolve.
+		assert!(Balances::transfer(Origin::signed(alice), Address::Index(7), DOLLARS).is_err());
+	});
+}